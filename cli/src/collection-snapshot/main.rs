@@ -0,0 +1,34 @@
+use database::{repositories, ConnectOptions, Database};
+use std::time::Duration;
+
+#[tokio::main]
+async fn main() {
+    dotenv::dotenv().ok();
+    let db_url = std::env::var("DATABASE_URL").expect("db_url must be set");
+    let interval = std::env::var("COLLECTION_SNAPSHOT_INTERVAL")
+        .ok()
+        .and_then(|interval| interval.parse::<u64>().ok())
+        .unwrap_or(3600);
+
+    let mut opt = ConnectOptions::new(db_url);
+    opt.sqlx_logging(false);
+
+    let db = Database::connect(opt).await.unwrap();
+
+    let mut interval = tokio::time::interval(Duration::from_secs(interval));
+
+    loop {
+        interval.tick().await;
+
+        // snapshot the freshest numbers, the stats job may not have run for a while
+        if let Err(error) = repositories::collection_stats::refresh_all(&db).await {
+            eprintln!("unexpected error when refresh collection stats {}", error);
+            continue;
+        }
+
+        match repositories::collection_snapshot::create_for_all_collections(&db).await {
+            Ok(count) => println!("done snapshot {} collections", count),
+            Err(error) => eprintln!("unexpected error when snapshot collections {}", error),
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use database::{repositories, ConnectOptions, Database};
+use std::time::Duration;
+
+#[tokio::main]
+async fn main() {
+    dotenv::dotenv().ok();
+    let db_url = std::env::var("DATABASE_URL").expect("db_url must be set");
+    let interval = std::env::var("COLLECTION_STATS_INTERVAL")
+        .ok()
+        .and_then(|interval| interval.parse::<u64>().ok())
+        .unwrap_or(60);
+
+    let mut opt = ConnectOptions::new(db_url);
+    opt.sqlx_logging(false);
+
+    let db = Database::connect(opt).await.unwrap();
+
+    let mut interval = tokio::time::interval(Duration::from_secs(interval));
+
+    loop {
+        interval.tick().await;
+
+        match repositories::collection_stats::refresh_all(&db).await {
+            Ok(_) => println!("done refresh collection stats"),
+            Err(error) => eprintln!("unexpected error when refresh collection stats {}", error),
+        }
+    }
+}
@@ -1,24 +1,60 @@
 use crate::{
-    find_attribute, shared::create_nft_or_update_owner_or_just_find, Attribute, Event, Transaction,
+    block_time, find_attribute, invalidate_nft_cache, mark_rarity_stale, publish_market_event,
+    shared::{
+        create_nft_or_update_owner_or_just_find, resolve_nft, resolve_nft_metadata, ResolvedNft,
+    },
+    Attribute, Event, Transaction,
 };
-use anyhow::Ok;
-use chrono::Utc;
 use database::{
-    repositories::tracing::{self as TracingRepository, CreateStreamTxParams},
+    prelude::DateTimeUtc,
+    repositories::{
+        self,
+        nft::UpdateNftMetadataParams,
+        tracing::{self as TracingRepository, CreateStreamTxParams},
+    },
     sea_orm_active_enums::StreamContext,
-    DatabaseConnection,
+    DatabaseConnection, DatabaseTransaction, TransactionTrait,
 };
-use service::CosmosClient;
+use service::{CacheConnection, CosmosClient, MarketEvent, MarketEventKind};
 
-static MINT_ACTION: &'static str = "mint";
-static TRANSFER_ACTION: &'static str = "transfer_nft";
-static SEND_ACTION: &'static str = "send_nft";
+static MINT_ACTION: &str = "mint";
+static TRANSFER_ACTION: &str = "transfer_nft";
+static SEND_ACTION: &str = "send_nft";
+static UPDATE_NFT_INFO_ACTION: &str = "update_nft_info";
 
-pub async fn tx_handler(db: &DatabaseConnection, client: &CosmosClient, tx: Transaction) {
-    let Transaction { tx_hash, events } = tx;
+pub async fn tx_handler(
+    db: &DatabaseConnection,
+    client: &CosmosClient,
+    cache: &CacheConnection,
+    tx: Transaction,
+) -> bool {
+    let Transaction {
+        tx_hash,
+        events,
+        height,
+        ..
+    } = tx;
 
     let events = retrieve_cw721_events(events);
 
+    if events.is_empty() {
+        return true;
+    }
+
+    let date = match block_time(client, height).await {
+        Ok(date) => date,
+        Err(error) => {
+            eprintln!(
+                "unexpected error when get block time of cw721 tx {} {}",
+                tx_hash, error
+            );
+            return false;
+        }
+    };
+
+    // remote data is resolved before the db transaction opens so it only spans the writes
+    let mut resolved = vec![];
+
     for event in events {
         let action = event
             .attributes
@@ -27,46 +63,67 @@ pub async fn tx_handler(db: &DatabaseConnection, client: &CosmosClient, tx: Tran
             .map(|attribute| attribute.value.to_owned())
             .unwrap_or_default();
 
-        let result = if action == MINT_ACTION {
-            hanlde_mint(db, client, &event).await
-        } else if action == TRANSFER_ACTION {
-            hanlde_transfer(db, client, &event).await
-        } else if action == SEND_ACTION {
-            hanlde_send(db, client, &event).await
-        } else {
-            println!("unexpected action {} event {:#?}", action, event);
-            Ok(())
-        };
+        match resolve(db, client, &action, &event).await {
+            Ok(remote) => resolved.push((action, event, remote)),
+            Err(error) => return record_failure(db, action, &event, &tx_hash, date, error).await,
+        }
+    }
 
-        if let Err(error) = result {
+    // every event of the tx and its stream_tx record are written together or not at all
+    let txn = match db.begin().await {
+        Ok(txn) => txn,
+        Err(error) => {
             eprintln!(
-                "unexpected error when handle cw721 event {} {} \n>>{}",
-                action, tx_hash, error
+                "unexpected error when begin db transaction {} {}",
+                tx_hash, error
             );
+            return false;
+        }
+    };
 
-            TracingRepository::create_stream_tx(
-                db,
-                CreateStreamTxParams {
-                    action,
-                    context: StreamContext::Cwr721,
-                    date: Utc::now().into(),
-                    event: serde_json::json!(event),
-                    is_failure: true,
-                    tx_hash: tx_hash.to_owned(),
-                    message: Some(error.to_string()),
-                },
-            )
-            .await
-            .unwrap_or_else(|e| eprintln!("unexpected error when create tracing tx {}", e));
-        } else {
-            println!("done handle cw721 event {} {}", action, tx_hash);
+    let mut touched = vec![];
+    let mut stale = vec![];
+    let mut market_events = vec![];
+
+    for (action, event, remote) in resolved {
+        let (token, changes_traits) = match &remote {
+            Remote::Nft(nft) => (
+                (nft.token_address.to_owned(), nft.token_id.to_owned()),
+                nft.is_new(),
+            ),
+            Remote::Metadata(metadata) => (
+                (
+                    metadata.token_address.to_owned(),
+                    metadata.token_id.to_owned(),
+                ),
+                true,
+            ),
+        };
+
+        let result = match remote {
+            Remote::Nft(nft) if action == MINT_ACTION => {
+                hanlde_mint(&txn, nft, &event, &tx_hash, date).await
+            }
+            Remote::Nft(nft) if action == TRANSFER_ACTION => {
+                hanlde_transfer(&txn, nft, &event, &tx_hash, date).await
+            }
+            Remote::Nft(nft) if action == SEND_ACTION => {
+                hanlde_send(&txn, nft, &event, &tx_hash, date).await
+            }
+            Remote::Metadata(metadata) => handle_update_nft_info(&txn, metadata).await,
+            Remote::Nft(_) => {
+                println!("unexpected action {} event {:#?}", action, event);
+                Ok(None)
+            }
+        };
 
-            TracingRepository::create_stream_tx(
-                db,
+        let result = match result {
+            Ok(market_event) => TracingRepository::create_stream_tx(
+                &txn,
                 CreateStreamTxParams {
-                    action,
+                    action: action.to_owned(),
                     context: StreamContext::Cwr721,
-                    date: Utc::now().into(),
+                    date: date.into(),
                     event: serde_json::json!(event),
                     is_failure: false,
                     tx_hash: tx_hash.to_owned(),
@@ -74,75 +131,227 @@ pub async fn tx_handler(db: &DatabaseConnection, client: &CosmosClient, tx: Tran
                 },
             )
             .await
-            .unwrap_or_else(|e| eprintln!("unexpected error when create tracing tx {}", e));
+            .map(|_| market_event)
+            .map_err(anyhow::Error::from),
+            Err(error) => Err(error),
+        };
+
+        match result {
+            Ok(market_event) => market_events.extend(market_event),
+            Err(error) => {
+                txn.rollback()
+                    .await
+                    .unwrap_or_else(|e| eprintln!("unexpected error when rollback {}", e));
+
+                return record_failure(db, action, &event, &tx_hash, date, error).await;
+            }
         }
+
+        if changes_traits {
+            stale.push(token.0.to_owned());
+        }
+
+        touched.push(token);
     }
+
+    if let Err(error) = txn.commit().await {
+        eprintln!(
+            "unexpected error when commit cw721 tx {} {}",
+            tx_hash, error
+        );
+        return false;
+    }
+
+    println!("done handle cw721 tx {}", tx_hash);
+
+    for (token_address, token_id) in touched {
+        invalidate_nft_cache(cache, &token_address, &token_id).await;
+    }
+
+    stale.sort();
+    stale.dedup();
+
+    for collection_address in stale {
+        mark_rarity_stale(cache, &collection_address).await;
+    }
+
+    for market_event in market_events {
+        publish_market_event(cache, market_event).await;
+    }
+
+    true
 }
 
 async fn hanlde_transfer(
-    db: &DatabaseConnection,
-    client: &CosmosClient,
+    db: &DatabaseTransaction,
+    nft: ResolvedNft,
     event: &Event,
-) -> anyhow::Result<()> {
-    let token_address = find_attribute(event, "_contract_address")?;
-    let token_id = find_attribute(event, "token_id")?;
+    tx_hash: &str,
+    date: DateTimeUtc,
+) -> anyhow::Result<Option<MarketEvent>> {
+    let token_address = nft.token_address.to_owned();
+    let token_id = nft.token_id.to_owned();
     let recipient = find_attribute(event, "recipient")?;
 
-    create_nft_or_update_owner_or_just_find(db, client, token_address, token_id, Some(recipient))
-        .await?;
+    create_nft_or_update_owner_or_just_find(db, nft, Some(recipient.to_owned())).await?;
 
-    Ok(())
+    Ok(Some(MarketEvent {
+        kind: MarketEventKind::Transfer,
+        collection_address: token_address,
+        token_id,
+        tx_hash: tx_hash.to_owned(),
+        marketplace: None,
+        seller: find_attribute(event, "sender").ok(),
+        buyer: Some(recipient),
+        price: None,
+        denom: None,
+        date: date.timestamp(),
+    }))
 }
 
 async fn hanlde_send(
-    db: &DatabaseConnection,
-    client: &CosmosClient,
+    db: &DatabaseTransaction,
+    nft: ResolvedNft,
     event: &Event,
-) -> anyhow::Result<()> {
-    let token_address = find_attribute(event, "_contract_address")?;
-    let token_id = find_attribute(event, "token_id")?;
+    tx_hash: &str,
+    date: DateTimeUtc,
+) -> anyhow::Result<Option<MarketEvent>> {
+    let token_address = nft.token_address.to_owned();
+    let token_id = nft.token_id.to_owned();
     let recipient = find_attribute(event, "recipient")?;
 
-    create_nft_or_update_owner_or_just_find(db, client, token_address, token_id, Some(recipient))
-        .await?;
+    create_nft_or_update_owner_or_just_find(db, nft, Some(recipient.to_owned())).await?;
 
-    Ok(())
+    Ok(Some(MarketEvent {
+        kind: MarketEventKind::Transfer,
+        collection_address: token_address,
+        token_id,
+        tx_hash: tx_hash.to_owned(),
+        marketplace: None,
+        seller: find_attribute(event, "sender").ok(),
+        buyer: Some(recipient),
+        price: None,
+        denom: None,
+        date: date.timestamp(),
+    }))
 }
 
 async fn hanlde_mint(
+    db: &DatabaseTransaction,
+    nft: ResolvedNft,
+    event: &Event,
+    tx_hash: &str,
+    date: DateTimeUtc,
+) -> anyhow::Result<Option<MarketEvent>> {
+    let token_address = nft.token_address.to_owned();
+    let token_id = nft.token_id.to_owned();
+    let owner = find_attribute(event, "owner")?;
+
+    create_nft_or_update_owner_or_just_find(db, nft, Some(owner.to_owned())).await?;
+
+    Ok(Some(MarketEvent {
+        kind: MarketEventKind::Mint,
+        collection_address: token_address,
+        token_id,
+        tx_hash: tx_hash.to_owned(),
+        marketplace: None,
+        seller: None,
+        buyer: Some(owner),
+        price: None,
+        denom: None,
+        date: date.timestamp(),
+    }))
+}
+
+async fn handle_update_nft_info(
+    db: &DatabaseTransaction,
+    metadata: UpdateNftMetadataParams,
+) -> anyhow::Result<Option<MarketEvent>> {
+    // an nft not indexed yet is created with the new metadata when it is first seen
+    repositories::nft::update_metadata(db, metadata).await?;
+
+    Ok(None)
+}
+
+// remote data of a cw721 event
+enum Remote {
+    Nft(ResolvedNft),
+    Metadata(UpdateNftMetadataParams),
+}
+
+async fn resolve(
     db: &DatabaseConnection,
     client: &CosmosClient,
+    action: &str,
     event: &Event,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<Remote> {
     let token_address = find_attribute(event, "_contract_address")?;
     let token_id = find_attribute(event, "token_id")?;
-    let owner = find_attribute(event, "owner")?;
 
-    create_nft_or_update_owner_or_just_find(db, client, token_address, token_id, Some(owner))
-        .await?;
+    if action == UPDATE_NFT_INFO_ACTION {
+        let metadata = resolve_nft_metadata(client, token_address, token_id).await?;
 
-    Ok(())
+        return Ok(Remote::Metadata(metadata));
+    }
+
+    let nft = resolve_nft(db, client, token_address, token_id).await?;
+
+    Ok(Remote::Nft(nft))
 }
 
-fn retrieve_cw721_events(events: Vec<Event>) -> Vec<Event> {
+// a recorded failure counts as handled, the checkpoint moves past it
+async fn record_failure(
+    db: &DatabaseConnection,
+    action: String,
+    event: &Event,
+    tx_hash: &str,
+    date: DateTimeUtc,
+    error: anyhow::Error,
+) -> bool {
+    eprintln!(
+        "unexpected error when handle cw721 event {} {} \n>>{}",
+        action, tx_hash, error
+    );
+
+    TracingRepository::create_stream_tx(
+        db,
+        CreateStreamTxParams {
+            action,
+            context: StreamContext::Cwr721,
+            date: date.into(),
+            event: serde_json::json!(event),
+            is_failure: true,
+            tx_hash: tx_hash.to_owned(),
+            message: Some(error.to_string()),
+        },
+    )
+    .await
+    .map_err(|e| eprintln!("unexpected error when create tracing tx {}", e))
+    .is_ok()
+}
+
+pub fn is_cw721_event(event: &Event) -> bool {
     fn is_cw721_action_attribute(attribue: &Attribute) -> bool {
         let Attribute { key, value } = attribue;
 
         if key != "action" {
             false
         } else {
-            value == MINT_ACTION || value == TRANSFER_ACTION || value == SEND_ACTION
+            value == MINT_ACTION
+                || value == TRANSFER_ACTION
+                || value == SEND_ACTION
+                || value == UPDATE_NFT_INFO_ACTION
         }
     }
 
-    fn is_cw721_event(event: &Event) -> bool {
-        event.r#type == "wasm"
-            && event
-                .attributes
-                .iter()
-                .find(|attribute| is_cw721_action_attribute(attribute))
-                .is_some()
-    }
+    event.r#type == "wasm"
+        && event
+            .attributes
+            .iter()
+            .find(|attribute| is_cw721_action_attribute(attribute))
+            .is_some()
+}
 
+fn retrieve_cw721_events(events: Vec<Event>) -> Vec<Event> {
     events.into_iter().filter(is_cw721_event).collect()
 }
@@ -0,0 +1,56 @@
+use cli::{
+    evm::tx_handler,
+    health::{spawn_health_server, StreamStatus},
+    listen_shutdown, stream_handler, RPC_URL,
+};
+use database::{repositories, sea_orm_active_enums::StreamContext, ConnectOptions, Database};
+use service::{connect_cache, CosmosClient};
+use std::sync::Arc;
+use tendermint_rpc::query::{EventType, Query};
+
+#[tokio::main]
+async fn main() {
+    dotenv::dotenv().ok();
+    let db_url = std::env::var("DATABASE_URL").expect("db_url must be set");
+    let redis_url = std::env::var("REDIS_URL").unwrap_or("redis://127.0.0.1/".to_owned());
+    let cosmos_client = CosmosClient::from(tendermint_rpc::HttpClient::new(RPC_URL).unwrap());
+
+    let mut opt = ConnectOptions::new(db_url);
+    opt.sqlx_logging(false);
+
+    let db = Database::connect(opt).await.unwrap();
+    let cache = connect_cache(&redis_url).await.unwrap();
+
+    let query = Query::from(EventType::Tx).and_eq(
+        "message.action",
+        "/seiprotocol.seichain.evm.MsgEVMTransaction",
+    );
+
+    let checkpoint = repositories::stream_checkpoint::find_height(&db, StreamContext::Evm)
+        .await
+        .unwrap();
+
+    let status = Arc::new(StreamStatus::new(StreamContext::Evm, checkpoint));
+
+    spawn_health_server(status.clone());
+
+    let shutdown = listen_shutdown();
+
+    while !*shutdown.borrow() {
+        if let Err(error) = stream_handler(
+            &db,
+            &cosmos_client,
+            &cache,
+            &query,
+            &status,
+            shutdown.clone(),
+            tx_handler,
+        )
+        .await
+        {
+            eprintln!("{}", error)
+        }
+    }
+
+    println!("stream stopped");
+}
@@ -0,0 +1,481 @@
+use crate::{
+    block_time, invalidate_nft_cache, mark_rarity_stale, publish_market_event,
+    shared::{
+        self, ComputeSaleFeesParams, CreateActivityTransactionAndPointOnSaleParams, ResolvedNft,
+    },
+    Transaction,
+};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use database::{
+    prelude::{DateTimeUtc, Decimal},
+    repositories::{self, evm_contract::UpsertEvmContractParams, tracing::CreateStreamTxParams},
+    sea_orm_active_enums::{Marketplace, StreamContext},
+    ConnectionTrait, DatabaseConnection, DatabaseTransaction, TransactionTrait,
+};
+use service::{
+    decode_evm_logs, CacheConnection, CosmosClient, Erc721Transfer, EvmLog, ItemType, MarketEvent,
+    MarketEventKind, OrderItem, PointerType, SEI_DENOM, ZERO_ADDRESS,
+};
+use std::str::FromStr;
+
+static TRANSFER_ACTION: &str = "erc721_transfer";
+
+// sei and wsei have 18 decimals on evm, sales paid with them are stored in usei like cosmos ones
+static WEI_PER_USEI: u64 = 1_000_000_000_000;
+static WSEI_ADDRESS: &str = "0xe30fedd158a2e3b13e9badaeabafc5516e95e8c7";
+
+pub async fn tx_handler(
+    db: &DatabaseConnection,
+    client: &CosmosClient,
+    cache: &CacheConnection,
+    tx: Transaction,
+) -> bool {
+    let Transaction {
+        tx_hash,
+        data,
+        height,
+        ..
+    } = tx;
+
+    let logs = match data.map(|data| BASE64_STANDARD.decode(data)) {
+        Some(Ok(data)) => decode_evm_logs(&data).unwrap_or_else(|e| {
+            eprintln!("unexpected error when decode evm logs of {} {}", tx_hash, e);
+            vec![]
+        }),
+        _ => vec![],
+    };
+
+    // synthetic logs mirror cw721 events, the cw721 stream indexes those
+    let erc721_transfers = logs
+        .iter()
+        .filter(|log| !log.synthetic)
+        .filter_map(EvmLog::to_erc721_transfer)
+        .collect::<Vec<_>>();
+
+    if erc721_transfers.is_empty() {
+        return true;
+    }
+
+    let date = match block_time(client, height).await {
+        Ok(date) => date,
+        Err(error) => {
+            eprintln!(
+                "unexpected error when get block time of evm tx {} {}",
+                tx_hash, error
+            );
+            return false;
+        }
+    };
+
+    // remote data is resolved before the db transaction opens so it only spans the writes
+    let mut resolved = vec![];
+
+    for transfer in erc721_transfers {
+        match resolve(db, client, &transfer).await {
+            Ok(Some(remote)) => resolved.push((transfer, remote)),
+            Ok(None) => {}
+            Err(error) => return record_failure(db, &transfer, &tx_hash, date, error).await,
+        }
+    }
+
+    if resolved.is_empty() {
+        return true;
+    }
+
+    // every transfer of the tx and its stream_tx record are written together or not at all
+    let txn = match db.begin().await {
+        Ok(txn) => txn,
+        Err(error) => {
+            eprintln!(
+                "unexpected error when begin db transaction {} {}",
+                tx_hash, error
+            );
+            return false;
+        }
+    };
+
+    let mut stale = vec![];
+    let mut market_events = vec![];
+
+    for (transfer, remote) in resolved {
+        if remote.nft.is_new() {
+            stale.push(remote.nft.token_address.to_owned());
+        }
+
+        let result = handle_erc721_transfer(&txn, remote, &transfer, &logs, &tx_hash, date).await;
+
+        let result = match result {
+            Ok(market_event) => repositories::tracing::create_stream_tx(
+                &txn,
+                CreateStreamTxParams {
+                    action: TRANSFER_ACTION.to_owned(),
+                    context: StreamContext::Evm,
+                    date: date.into(),
+                    event: serde_json::json!(transfer),
+                    is_failure: false,
+                    tx_hash: tx_hash.to_owned(),
+                    message: None,
+                },
+            )
+            .await
+            .map(|_| market_event)
+            .map_err(anyhow::Error::from),
+            Err(error) => Err(error),
+        };
+
+        match result {
+            Ok(market_event) => market_events.extend(market_event),
+            Err(error) => {
+                txn.rollback()
+                    .await
+                    .unwrap_or_else(|e| eprintln!("unexpected error when rollback {}", e));
+
+                return record_failure(db, &transfer, &tx_hash, date, error).await;
+            }
+        }
+    }
+
+    if let Err(error) = txn.commit().await {
+        eprintln!("unexpected error when commit evm tx {} {}", tx_hash, error);
+        return false;
+    }
+
+    println!("done handle evm tx {}", tx_hash);
+
+    stale.sort();
+    stale.dedup();
+
+    for collection_address in stale {
+        mark_rarity_stale(cache, &collection_address).await;
+    }
+
+    for market_event in market_events {
+        invalidate_nft_cache(
+            cache,
+            &market_event.collection_address,
+            &market_event.token_id,
+        )
+        .await;
+        publish_market_event(cache, market_event).await;
+    }
+
+    true
+}
+
+// remote data of an erc721 transfer
+struct Remote {
+    nft: ResolvedNft,
+    buyer: String,
+    seller: Option<String>,
+}
+
+async fn resolve(
+    db: &DatabaseConnection,
+    client: &CosmosClient,
+    transfer: &Erc721Transfer,
+) -> anyhow::Result<Option<Remote>> {
+    let token_id = transfer.token_id.to_owned();
+
+    let nft = match find_collection(db, client, &transfer.contract).await? {
+        Some(Collection::Cw721(address)) => {
+            shared::resolve_nft(db, client, address, token_id).await?
+        }
+        Some(Collection::Erc721(address)) => {
+            shared::resolve_erc721_nft(db, client, address, token_id).await?
+        }
+        None => return Ok(None),
+    };
+
+    let buyer = to_sei_address(client, &transfer.to).await?;
+
+    let seller = match transfer.from.as_str() {
+        from if from == ZERO_ADDRESS => None,
+        from => Some(to_sei_address(client, from).await?),
+    };
+
+    Ok(Some(Remote { nft, buyer, seller }))
+}
+
+// true once the failure is recorded
+async fn record_failure(
+    db: &DatabaseConnection,
+    transfer: &Erc721Transfer,
+    tx_hash: &str,
+    date: DateTimeUtc,
+    error: anyhow::Error,
+) -> bool {
+    eprintln!(
+        "unexpected error when handle evm event {} {} \n>>{}",
+        TRANSFER_ACTION, tx_hash, error
+    );
+
+    repositories::tracing::create_stream_tx(
+        db,
+        CreateStreamTxParams {
+            action: TRANSFER_ACTION.to_owned(),
+            context: StreamContext::Evm,
+            date: date.into(),
+            event: serde_json::json!(transfer),
+            is_failure: true,
+            tx_hash: tx_hash.to_owned(),
+            message: Some(error.to_string()),
+        },
+    )
+    .await
+    .map_err(|e| eprintln!("unexpected error when create tracing tx {}", e))
+    .is_ok()
+}
+
+async fn handle_erc721_transfer(
+    db: &DatabaseTransaction,
+    remote: Remote,
+    transfer: &Erc721Transfer,
+    logs: &[EvmLog],
+    tx_hash: &str,
+    date: DateTimeUtc,
+) -> anyhow::Result<Option<MarketEvent>> {
+    let Remote { nft, buyer, seller } = remote;
+
+    let token_address = nft.token_address.to_owned();
+    let token_id = nft.token_id.to_owned();
+
+    let nft_id =
+        shared::create_nft_or_update_owner_or_just_find(db, nft, Some(buyer.to_owned())).await?;
+
+    let sale = match seller {
+        Some(_) => find_sale(logs, transfer)?,
+        None => None,
+    };
+
+    let (Some(seller), Some(sale)) = (seller.to_owned(), sale) else {
+        return Ok(Some(MarketEvent {
+            // the seller is none only for a transfer from the zero address
+            kind: match seller {
+                Some(_) => MarketEventKind::Transfer,
+                None => MarketEventKind::Mint,
+            },
+            collection_address: token_address,
+            token_id,
+            tx_hash: tx_hash.to_owned(),
+            marketplace: None,
+            seller,
+            buyer: Some(buyer),
+            price: None,
+            denom: None,
+            date: date.timestamp(),
+        }));
+    };
+
+    let fees = shared::compute_sale_fees(
+        db,
+        ComputeSaleFeesParams {
+            collection_address: &token_address,
+            marketplace: &Marketplace::Evm,
+            price: sale.price,
+            proceeds: sale.proceeds,
+        },
+    )
+    .await?;
+
+    repositories::nft::delete_listing_if_exist(db, nft_id).await?;
+
+    shared::create_activity_transaction_and_point_on_sale(
+        db,
+        CreateActivityTransactionAndPointOnSaleParams {
+            buyer: buyer.to_owned(),
+            collection_address: token_address.to_owned(),
+            date,
+            denom: sale.denom.to_owned(),
+            marketplace: Marketplace::Evm,
+            metadata: serde_json::json!({ "evm_contract": transfer.contract }),
+            nft_id,
+            price: sale.price.to_string(),
+            seller: seller.to_owned(),
+            tx_hash: tx_hash.to_owned(),
+            fees,
+        },
+    )
+    .await?;
+
+    repositories::collection_stats::refresh(db, &token_address).await?;
+
+    Ok(Some(MarketEvent {
+        kind: MarketEventKind::Sale,
+        collection_address: token_address,
+        token_id,
+        tx_hash: tx_hash.to_owned(),
+        marketplace: Some(Marketplace::Evm.into()),
+        seller: Some(seller),
+        buyer: Some(buyer),
+        price: Some(sale.price.to_string()),
+        denom: Some(sale.denom),
+        date: date.timestamp(),
+    }))
+}
+
+enum Collection {
+    Cw721(String),
+    Erc721(String),
+}
+
+// nfts of an erc721 with a cw721 pointer are keyed by the cw721 address, a native erc721 is keyed
+// by its own address. none means the contract is itself the pointer of a cw721 the cw721 stream
+// already indexes
+async fn find_collection(
+    db: &impl ConnectionTrait,
+    client: &CosmosClient,
+    address: &str,
+) -> anyhow::Result<Option<Collection>> {
+    if let Some(contract) = repositories::evm_contract::find_by_address(db, address).await? {
+        if contract.is_cw721_pointer {
+            return Ok(None);
+        }
+
+        return Ok(Some(match contract.cw721_address {
+            Some(cw721_address) => Collection::Cw721(cw721_address),
+            // kept native even if a pointer is registered later, its nfts would be split otherwise
+            None => Collection::Erc721(address.to_owned()),
+        }));
+    }
+
+    let pointee = client.get_evm_pointee(PointerType::Erc721, address).await?;
+
+    if pointee.exists {
+        repositories::evm_contract::upsert(
+            db,
+            UpsertEvmContractParams {
+                address: address.to_owned(),
+                cw721_address: Some(pointee.pointee),
+                is_cw721_pointer: true,
+            },
+        )
+        .await?;
+
+        return Ok(None);
+    }
+
+    let pointer = client.get_evm_pointer(PointerType::Cw721, address).await?;
+
+    let cw721_address = Some(pointer.pointer).filter(|_| pointer.exists);
+
+    repositories::evm_contract::upsert(
+        db,
+        UpsertEvmContractParams {
+            address: address.to_owned(),
+            cw721_address: cw721_address.to_owned(),
+            is_cw721_pointer: false,
+        },
+    )
+    .await?;
+
+    Ok(Some(match cw721_address {
+        Some(cw721_address) => Collection::Cw721(cw721_address),
+        None => Collection::Erc721(address.to_owned()),
+    }))
+}
+
+// owners are unified on sei addresses, a wallet that never linked its evm address keeps it
+async fn to_sei_address(client: &CosmosClient, evm_address: &str) -> anyhow::Result<String> {
+    let res = client.get_sei_address(evm_address).await?;
+
+    match res.associated && !res.sei_address.is_empty() {
+        true => Ok(res.sei_address),
+        false => Ok(evm_address.to_owned()),
+    }
+}
+
+struct Sale {
+    price: Decimal,
+    denom: String,
+    // what the seller received, the rest of the price went to fees and royalties
+    proceeds: Decimal,
+}
+
+// a sale is the seaport order of a marketplace contract that traded the transferred nft,
+// a bundle is priced evenly across its nfts
+fn find_sale(logs: &[EvmLog], transfer: &Erc721Transfer) -> anyhow::Result<Option<Sale>> {
+    let marketplaces = std::env::var("EVM_MARKETPLACE_CONTRACTS").unwrap_or_default();
+
+    let marketplaces = marketplaces
+        .split(',')
+        .map(|address| address.trim().to_lowercase())
+        .filter(|address| !address.is_empty())
+        .collect::<Vec<_>>();
+
+    let order = logs
+        .iter()
+        .filter(|log| marketplaces.contains(&log.address))
+        .filter_map(EvmLog::to_order_fulfilled)
+        .find(|order| order.trades(&transfer.contract, &transfer.token_id));
+
+    let Some(order) = order else {
+        return Ok(None);
+    };
+
+    // a listing offers the nft against a payment, an accepted bid offers the payment
+    let listing = order
+        .offer
+        .iter()
+        .any(|item| item.is_nft(&transfer.contract, &transfer.token_id));
+
+    let (nft_side, payment_side) = match listing {
+        true => (&order.offer, &order.consideration),
+        false => (&order.consideration, &order.offer),
+    };
+
+    let payments = payment_side
+        .iter()
+        .filter(|item| item.is_payment())
+        .map(to_denom_amount)
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let Some((denom, _)) = payments.first() else {
+        return Ok(None);
+    };
+
+    if payments.iter().any(|(other, _)| other != denom) {
+        anyhow::bail!("unexpected error order paid in several denoms");
+    }
+
+    let paid = payments.iter().map(|(_, amount)| *amount).sum::<Decimal>();
+
+    // the consideration of an accepted bid holds the fees the seller pays out of the offer
+    let considered = order
+        .consideration
+        .iter()
+        .filter(|item| item.is_payment())
+        .filter(|item| !listing || item.recipient.as_deref() == Some(transfer.from.as_str()))
+        .map(to_denom_amount)
+        .collect::<anyhow::Result<Vec<_>>>()?
+        .into_iter()
+        .filter(|(other, _)| other == denom)
+        .map(|(_, amount)| amount)
+        .sum::<Decimal>();
+
+    let proceeds = match listing {
+        true => considered,
+        false => paid - considered,
+    };
+
+    let nfts = nft_side
+        .iter()
+        .filter(|item| item.item_type == ItemType::Erc721)
+        .count()
+        .max(1);
+
+    Ok(Some(Sale {
+        price: paid / Decimal::from(nfts),
+        denom: denom.to_owned(),
+        proceeds: proceeds / Decimal::from(nfts),
+    }))
+}
+
+fn to_denom_amount(item: &OrderItem) -> anyhow::Result<(String, Decimal)> {
+    let amount = Decimal::from_str(&item.amount)?;
+
+    if item.item_type == ItemType::Native || item.token == WSEI_ADDRESS {
+        return Ok((SEI_DENOM.to_owned(), amount / Decimal::from(WEI_PER_USEI)));
+    }
+
+    Ok((item.token.to_owned(), amount))
+}
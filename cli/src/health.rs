@@ -0,0 +1,131 @@
+use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
+use chrono::Utc;
+use database::sea_orm_active_enums::StreamContext;
+use serde::Serialize;
+use std::sync::{
+    atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering},
+    Arc,
+};
+
+// shared by a stream and its health endpoint, zero stands for never
+pub struct StreamStatus {
+    pub context: StreamContext,
+    connected: AtomicBool,
+    last_event_at: AtomicI64,
+    last_height: AtomicU64,
+    // lowest height of a tx that was neither handled nor recorded as failed, the checkpoint stays
+    // below it
+    first_failed_height: AtomicU64,
+}
+
+#[derive(Serialize)]
+pub struct HealthReport {
+    pub context: StreamContext,
+    pub connected: bool,
+    pub last_event_at: Option<i64>,
+    pub last_height: Option<u64>,
+    pub checkpoint: Option<u64>,
+}
+
+impl StreamStatus {
+    pub fn new(context: StreamContext, checkpoint: Option<u64>) -> Self {
+        Self {
+            context,
+            connected: AtomicBool::new(false),
+            last_event_at: AtomicI64::new(0),
+            last_height: AtomicU64::new(checkpoint.unwrap_or_default()),
+            first_failed_height: AtomicU64::new(u64::MAX),
+        }
+    }
+
+    pub fn set_connected(&self, connected: bool) {
+        self.connected.store(connected, Ordering::Relaxed);
+    }
+
+    pub fn on_event(&self) {
+        self.last_event_at
+            .store(Utc::now().timestamp(), Ordering::Relaxed);
+    }
+
+    // workers finish out of order, the height only moves forward
+    pub fn on_processed(&self, height: Option<u64>) {
+        if let Some(height) = height {
+            self.last_height.fetch_max(height, Ordering::Relaxed);
+        }
+    }
+
+    pub fn on_failed(&self, height: Option<u64>) {
+        if let Some(height) = height {
+            self.first_failed_height
+                .fetch_min(height, Ordering::Relaxed);
+        }
+    }
+
+    pub fn last_height(&self) -> Option<u64> {
+        Some(self.last_height.load(Ordering::Relaxed)).filter(|height| *height > 0)
+    }
+
+    // every tx up to it was handled, a resumed stream catches up from there
+    pub fn checkpoint(&self) -> Option<u64> {
+        let failed = self.first_failed_height.load(Ordering::Relaxed);
+
+        self.last_height()
+            .map(|height| height.min(failed.saturating_sub(1)))
+            .filter(|height| *height > 0)
+    }
+
+    pub fn report(&self) -> HealthReport {
+        HealthReport {
+            context: self.context.to_owned(),
+            connected: self.connected.load(Ordering::Relaxed),
+            last_event_at: Some(self.last_event_at.load(Ordering::Relaxed))
+                .filter(|date| *date > 0),
+            last_height: self.last_height(),
+            checkpoint: self.checkpoint(),
+        }
+    }
+}
+
+// each stream process gets its own port, no port means no endpoint
+pub fn spawn_health_server(status: Arc<StreamStatus>) {
+    let Some(port) = std::env::var("HEALTH_PORT")
+        .ok()
+        .and_then(|port| port.parse::<u16>().ok())
+    else {
+        println!("HEALTH_PORT is not set, health endpoint is disabled");
+        return;
+    };
+
+    let app = Router::new()
+        .route("/health", get(health))
+        .route("/ready", get(ready))
+        .with_state(status);
+
+    tokio::spawn(async move {
+        let result = match tokio::net::TcpListener::bind(("0.0.0.0", port)).await {
+            Ok(listener) => axum::serve(listener, app).await,
+            Err(error) => Err(error),
+        };
+
+        if let Err(error) = result {
+            eprintln!("unexpected error when serve health endpoint {}", error);
+        }
+    });
+}
+
+async fn health(State(status): State<Arc<StreamStatus>>) -> Json<HealthReport> {
+    Json(status.report())
+}
+
+// ready only while subscribed, a stream stuck reconnecting answers 503
+async fn ready(State(status): State<Arc<StreamStatus>>) -> (StatusCode, Json<HealthReport>) {
+    let report = status.report();
+
+    let code = if report.connected {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (code, Json(report))
+}
@@ -1,23 +1,39 @@
 #![allow(unused_imports)]
 #![allow(dead_code)]
 pub mod cw721;
+pub mod evm;
+pub mod health;
 pub mod mrkt;
 pub mod pallet;
 pub mod shared;
 
 use anyhow::{anyhow, bail};
 use base64::{prelude::BASE64_STANDARD, Engine};
-use database::{query, DatabaseConnection};
-use futures_util::{SinkExt, StreamExt};
+use chrono::{DateTime, Utc};
+use database::{
+    prelude::{DateTimeUtc, Decimal},
+    query,
+    repositories::{self, wash_trade::WashTradeRules},
+    DatabaseConnection,
+};
+use futures_util::{future::join_all, SinkExt, StreamExt};
+use health::StreamStatus;
 use serde_json::Value;
-use service::CosmosClient;
+use service::{
+    decode_evm_logs, decode_tx, CacheConnection, CosmosClient, DecodedTx, EvmLog, MarketEvent,
+};
+use std::collections::hash_map::DefaultHasher;
 use std::future::Future;
-use tendermint_rpc::query::Query;
+use std::hash::{Hash, Hasher};
+use tendermint_rpc::{endpoint::tx, query::Query};
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::{mpsc, oneshot, watch};
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 
-pub static RPC_URL: &'static str = "https://rpc.sei-apis.com?x-apikey=06cf555f";
-static WSS_URL: &'static str = "wss://rpc.sei-apis.com/websocket?x-apikey=06cf555f";
-static INGORE_MESSAGE: &'static str = "{\"jsonrpc\":\"2.0\",\"id\":\"0\",\"result\":{}}";
+pub static RPC_URL: &str = "https://rpc.sei-apis.com?x-apikey=06cf555f";
+static WSS_URL: &str = "wss://rpc.sei-apis.com/websocket?x-apikey=06cf555f";
+static CATCH_UP_PAGE_SIZE: u8 = 100;
+static INGORE_MESSAGE: &str = "{\"jsonrpc\":\"2.0\",\"id\":\"0\",\"result\":{}}";
 
 pub trait FromJsonValue
 where
@@ -30,6 +46,10 @@ where
 pub struct Transaction {
     pub tx_hash: String,
     pub events: Vec<Event>,
+    // base64 result data, evm messages return their logs in it
+    pub data: Option<String>,
+    pub tx: Option<DecodedTx>,
+    pub height: Option<u64>,
 }
 
 #[derive(serde::Deserialize, serde::Serialize, Debug)]
@@ -47,35 +67,417 @@ pub struct Attribute {
 pub async fn stream_handler<'r, F, Fut>(
     db: &'r DatabaseConnection,
     cosmos_client: &'r CosmosClient,
-    msg_subcribe: &Message,
+    cache: &'r CacheConnection,
+    query: &Query,
+    status: &'r StreamStatus,
+    mut shutdown: watch::Receiver<bool>,
     tx_handler: F,
 ) -> anyhow::Result<()>
 where
-    F: Fn(&'r DatabaseConnection, &'r CosmosClient, Transaction) -> Fut,
-    Fut: Future<Output = ()> + 'r,
+    F: Fn(&'r DatabaseConnection, &'r CosmosClient, &'r CacheConnection, Transaction) -> Fut,
+    Fut: Future<Output = bool> + 'r,
 {
     let (ws_stream, _) = connect_async(WSS_URL).await?;
 
     let (mut write, mut read) = ws_stream.split();
 
-    write.send(msg_subcribe.to_owned()).await?;
+    write
+        .send(create_subcribe_message(query.to_owned()))
+        .await?;
 
-    while let Some(message) = read.next().await {
-        if let Message::Text(message) = message? {
-            if message != INGORE_MESSAGE {
-                let tx_result = serde_json::from_str::<Value>(&message)
-                    .map_err(|e| anyhow!("unxepected error can not parse raw msg, {}", e))
-                    .and_then(<Transaction as FromJsonValue>::try_from_value)?;
+    let StreamWorkers { count, queue_size } = stream_workers();
 
-                tx_handler(db, cosmos_client, tx_result).await
-            } else {
-                // we skip first message, so this time is perfect to tell that stream is working
-                println!("listening stream")
+    let (senders, receivers): (Vec<_>, Vec<_>) =
+        (0..count).map(|_| mpsc::channel::<Job>(queue_size)).unzip();
+
+    let tx_handler = &tx_handler;
+
+    // each worker handles its queue in order, workers run concurrently with each other
+    let workers = receivers.into_iter().map(|mut receiver| async move {
+        while let Some(job) = receiver.recv().await {
+            match job {
+                Job::Tx(tx) => {
+                    handle_tx(db, cosmos_client, cache, status, tx_handler, tx).await;
+                }
+                Job::Flush(done) => {
+                    done.send(()).ok();
+                }
+            }
+        }
+    });
+
+    let reader = async move {
+        let mut caught_up = 0;
+
+        loop {
+            let message = tokio::select! {
+                message = read.next() => message,
+                Ok(_) = shutdown.changed() => None,
+            };
+
+            let Some(message) = message else {
+                break;
+            };
+
+            if let Message::Text(message) = message? {
+                if message != INGORE_MESSAGE {
+                    status.on_event();
+
+                    let tx_result = serde_json::from_str::<Value>(&message)
+                        .map_err(|e| anyhow!("unxepected error can not parse raw msg, {}", e))
+                        .and_then(<Transaction as FromJsonValue>::try_from_value)?;
+
+                    // already handled by the catch up
+                    if tx_result.height.is_some_and(|height| height <= caught_up) {
+                        continue;
+                    }
+
+                    // a full queue holds the reader, the socket is not read faster than txs are handled
+                    if let Some(worker) = worker_of(&tx_result, senders.len()) {
+                        senders[worker]
+                            .send(Job::Tx(tx_result))
+                            .await
+                            .map_err(|_| {
+                                anyhow!("unexpected error stream worker {} stopped", worker)
+                            })?;
+
+                        continue;
+                    }
+
+                    // the collections of the tx are ordered by several workers, it is handled
+                    // here once every queued tx is
+                    let mut flushed = vec![];
+
+                    for (worker, sender) in senders.iter().enumerate() {
+                        let (done, flush) = oneshot::channel();
+
+                        sender.send(Job::Flush(done)).await.map_err(|_| {
+                            anyhow!("unexpected error stream worker {} stopped", worker)
+                        })?;
+
+                        flushed.push(flush);
+                    }
+
+                    join_all(flushed).await;
+
+                    handle_tx(db, cosmos_client, cache, status, tx_handler, tx_result).await;
+                } else {
+                    // we skip first message, so this time is perfect to tell that stream is working
+                    status.set_connected(true);
+                    println!("listening stream");
+
+                    // txs missed while the stream was down are handled before the live ones that
+                    // queue up on the socket meanwhile, the workers are idle until then
+                    caught_up = catch_up(
+                        db,
+                        cosmos_client,
+                        cache,
+                        query,
+                        status,
+                        &shutdown,
+                        tx_handler,
+                    )
+                    .await?;
+
+                    if *shutdown.borrow() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    };
+
+    // the reader drops the senders when it stops, workers drain what is queued before returning
+    let (result, _) = tokio::join!(reader, join_all(workers));
+
+    status.set_connected(false);
+
+    // nothing is in flight anymore, every tx up to the checkpoint is handled
+    if let Some(height) = status.checkpoint() {
+        repositories::stream_checkpoint::save(db, status.context.to_owned(), height)
+            .await
+            .unwrap_or_else(|e| eprintln!("unexpected error when save checkpoint {}", e));
+    }
+
+    result
+}
+
+async fn handle_tx<'r, F, Fut>(
+    db: &'r DatabaseConnection,
+    cosmos_client: &'r CosmosClient,
+    cache: &'r CacheConnection,
+    status: &StreamStatus,
+    tx_handler: &F,
+    tx: Transaction,
+) where
+    F: Fn(&'r DatabaseConnection, &'r CosmosClient, &'r CacheConnection, Transaction) -> Fut,
+    Fut: Future<Output = bool> + 'r,
+{
+    let height = tx.height;
+
+    // a tx neither handled nor recorded as failed holds the checkpoint so a restart handles it again
+    match tx_handler(db, cosmos_client, cache, tx).await {
+        true => status.on_processed(height),
+        false => status.on_failed(height),
+    }
+}
+
+// handles the txs from the checkpoint to the chain head in order, txs already recorded as handled
+// are skipped. returns the height handled up to, zero without a checkpoint to resume from. a
+// shutdown stops it between two blocks, the checkpoint then stays at the last block handled
+async fn catch_up<'r, F, Fut>(
+    db: &'r DatabaseConnection,
+    cosmos_client: &'r CosmosClient,
+    cache: &'r CacheConnection,
+    query: &Query,
+    status: &StreamStatus,
+    shutdown: &watch::Receiver<bool>,
+    tx_handler: &F,
+) -> anyhow::Result<u64>
+where
+    F: Fn(&'r DatabaseConnection, &'r CosmosClient, &'r CacheConnection, Transaction) -> Fut,
+    Fut: Future<Output = bool> + 'r,
+{
+    let Some(from) = status.checkpoint() else {
+        return Ok(0);
+    };
+
+    let to = cosmos_client.get_latest_height().await?;
+
+    // tx_search does not know the event type of subscriptions
+    let mut search = query
+        .to_owned()
+        .and_gt("tx.height", from)
+        .and_lte("tx.height", to);
+    search.event_type = None;
+
+    let mut page = 1;
+    let mut count = 0;
+    let mut handled = from;
+
+    loop {
+        let res = cosmos_client
+            .search_tx(search.to_owned(), page, CATCH_UP_PAGE_SIZE)
+            .await?;
+
+        for tx in res.txs {
+            let tx = Transaction::from(tx);
+
+            // the txs of a block are handled together, the checkpoint never splits one
+            if tx.height.is_some_and(|height| height > handled) {
+                if *shutdown.borrow() {
+                    println!("stopped catch up at height {}", handled);
+                    return Ok(handled);
+                }
+
+                handled = tx.height.unwrap_or(handled);
             }
+
+            count += 1;
+
+            if repositories::tracing::is_handled(db, status.context.to_owned(), &tx.tx_hash).await?
+            {
+                status.on_processed(tx.height);
+                continue;
+            }
+
+            handle_tx(db, cosmos_client, cache, status, tx_handler, tx).await;
         }
+
+        if page * CATCH_UP_PAGE_SIZE as u32 >= res.total_count {
+            break;
+        }
+
+        page += 1;
     }
 
-    Ok(())
+    status.on_processed(Some(to));
+
+    println!("caught up {} txs from height {} to {}", count, from, to);
+
+    Ok(to)
+}
+
+// resolves the receiver to true on SIGTERM or ctrl-c, streams stop reading and drain their queue
+pub fn listen_shutdown() -> watch::Receiver<bool> {
+    let (sender, receiver) = watch::channel(false);
+
+    tokio::spawn(async move {
+        let mut terminate = signal(SignalKind::terminate()).expect("can not listen SIGTERM");
+
+        tokio::select! {
+            _ = terminate.recv() => {},
+            _ = tokio::signal::ctrl_c() => {},
+        }
+
+        println!("shutting down stream");
+        sender.send(true).ok();
+
+        // keeps the sender alive so receivers never see it closed
+        std::future::pending::<()>().await;
+    });
+
+    receiver
+}
+
+enum Job {
+    Tx(Transaction),
+    // answered once every job queued before it is done
+    Flush(oneshot::Sender<()>),
+}
+
+struct StreamWorkers {
+    count: usize,
+    queue_size: usize,
+}
+
+fn stream_workers() -> StreamWorkers {
+    StreamWorkers {
+        count: std::env::var("STREAM_WORKERS")
+            .ok()
+            .and_then(|count| count.parse().ok())
+            .filter(|count| *count > 0)
+            .unwrap_or(8),
+        queue_size: std::env::var("STREAM_QUEUE_SIZE")
+            .ok()
+            .and_then(|size| size.parse().ok())
+            .filter(|size| *size > 0)
+            .unwrap_or(32),
+    }
+}
+
+// txs touching the same collection go to the same worker so they are handled in the order they
+// came, none when the collections of the tx are ordered by different workers
+pub fn worker_of(tx: &Transaction, count: usize) -> Option<usize> {
+    let worker = |key: &str| {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+
+        (hasher.finish() % count as u64) as usize
+    };
+
+    let mut workers = collections_of(tx)
+        .iter()
+        .map(|collection| worker(collection))
+        .collect::<Vec<_>>();
+
+    workers.sort();
+    workers.dedup();
+
+    match workers.as_slice() {
+        [] => Some(worker(&tx.tx_hash)),
+        [worker] => Some(*worker),
+        _ => None,
+    }
+}
+
+// marketplace events name the collection, cw721 events are emitted by the collection itself
+fn collections_of(tx: &Transaction) -> Vec<String> {
+    let from_events = tx.events.iter().filter_map(|event| {
+        find_attribute(event, "collection_address")
+            .ok()
+            .or_else(|| {
+                cw721::is_cw721_event(event)
+                    .then(|| find_attribute(event, "_contract_address").ok())
+                    .flatten()
+            })
+    });
+
+    let from_logs = tx
+        .data
+        .as_ref()
+        .and_then(|data| BASE64_STANDARD.decode(data).ok())
+        .and_then(|data| decode_evm_logs(&data).ok())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(EvmLog::to_erc721_transfer)
+        .map(|transfer| transfer.contract)
+        .collect::<Vec<_>>();
+
+    from_events.chain(from_logs).collect()
+}
+
+// a bank transfer as emitted in the tx events, `amount` is a coin list like `1000usei,5uatom`
+#[derive(Debug)]
+pub struct Transfer {
+    pub sender: String,
+    pub recipient: String,
+    pub amount: String,
+}
+
+impl Transfer {
+    pub fn amount_of(&self, denom: &str) -> Decimal {
+        self.amount
+            .split(',')
+            .filter_map(|coin| {
+                let split = coin.find(|c: char| !c.is_ascii_digit())?;
+                let (amount, coin_denom) = coin.split_at(split);
+
+                (coin_denom == denom)
+                    .then(|| amount.parse::<Decimal>().ok())
+                    .flatten()
+            })
+            .sum()
+    }
+}
+
+// one transfer event can carry several transfers, each one starts with its recipient
+pub fn retrieve_transfers(events: &[Event]) -> Vec<Transfer> {
+    let mut transfers = Vec::<Transfer>::new();
+
+    for event in events.iter().filter(|event| event.r#type == "transfer") {
+        for Attribute { key, value } in &event.attributes {
+            match (key.as_str(), transfers.last_mut()) {
+                ("recipient", _) => transfers.push(Transfer {
+                    recipient: value.to_owned(),
+                    sender: String::new(),
+                    amount: String::new(),
+                }),
+                ("sender", Some(transfer)) => transfer.sender = value.to_owned(),
+                ("amount", Some(transfer)) => transfer.amount = value.to_owned(),
+                _ => {}
+            }
+        }
+    }
+
+    transfers
+}
+
+// the events a contract event caused: the ones after it up to the next event of the same type or
+// the start of the next message, the bank transfers of its sub messages land there
+pub fn events_caused_by(events: &[Event], index: usize) -> &[Event] {
+    let Some(event) = events.get(index) else {
+        return &[];
+    };
+
+    let rest = &events[index + 1..];
+
+    let end = rest
+        .iter()
+        .position(|other| other.r#type == event.r#type || is_message_start(other))
+        .unwrap_or(rest.len());
+
+    &rest[..end]
+}
+
+// every message opens with a `message` event naming its action, modules add `message` events
+// without one
+fn is_message_start(event: &Event) -> bool {
+    event.r#type == "message"
+        && event
+            .attributes
+            .iter()
+            .any(|attribute| attribute.key == "action")
+}
+
+pub fn received_by(transfers: &[Transfer], wallet: &str, denom: &str) -> Decimal {
+    transfers
+        .iter()
+        .filter(|transfer| transfer.recipient == wallet)
+        .map(|transfer| transfer.amount_of(denom))
+        .sum()
 }
 
 pub fn find_attribute(event: &Event, key: &str) -> anyhow::Result<String> {
@@ -87,6 +489,92 @@ pub fn find_attribute(event: &Event, key: &str) -> anyhow::Result<String> {
         .ok_or(anyhow::anyhow!(format!("missing attribute {}", key)))
 }
 
+// the stream catches up on past txs, they are dated by their block and not by when they are handled
+pub async fn block_time(client: &CosmosClient, height: Option<u64>) -> anyhow::Result<DateTimeUtc> {
+    let Some(height) = height else {
+        return Ok(Utc::now());
+    };
+
+    let time = client.get_block_time(height).await?;
+
+    Ok(DateTime::parse_from_rfc3339(&time.to_rfc3339())?.with_timezone(&Utc))
+}
+
+// cached api responses must not outlive a change made by the stream
+pub async fn invalidate_nft_cache(cache: &CacheConnection, token_address: &str, token_id: &str) {
+    service::invalidate_nft(&mut cache.clone(), token_address, token_id)
+        .await
+        .unwrap_or_else(|e| eprintln!("unexpected error when invalidate nft cache {}", e));
+}
+
+// the rarity job recomputes the collection, ranks are not worth a recompute per mint
+pub async fn mark_rarity_stale(cache: &CacheConnection, collection_address: &str) {
+    service::mark_rarity_stale(&mut cache.clone(), collection_address)
+        .await
+        .unwrap_or_else(|e| eprintln!("unexpected error when mark rarity stale {}", e));
+}
+
+// subscribers are notified only after the change is persisted, a failed publish never fails the event
+pub async fn publish_market_event(cache: &CacheConnection, event: MarketEvent) {
+    service::publish_market_event(&mut cache.clone(), &event)
+        .await
+        .unwrap_or_else(|e| eprintln!("unexpected error when publish market event {}", e));
+}
+
+// stream and admin scan must judge sales by the same thresholds
+pub fn wash_trade_rules() -> WashTradeRules {
+    let default = WashTradeRules::default();
+
+    WashTradeRules {
+        circular_window_hours: std::env::var("WASH_TRADE_CIRCULAR_HOURS")
+            .ok()
+            .and_then(|hours| hours.parse().ok())
+            .unwrap_or(default.circular_window_hours),
+        floor_ratio: std::env::var("WASH_TRADE_FLOOR_RATIO")
+            .ok()
+            .and_then(|ratio| ratio.parse::<Decimal>().ok())
+            .unwrap_or(default.floor_ratio),
+    }
+}
+
+// a tx found by tx_search, the same tx as the stream would have sent
+impl From<tx::Response> for Transaction {
+    fn from(res: tx::Response) -> Self {
+        let events = res
+            .tx_result
+            .events
+            .into_iter()
+            .map(|event| Event {
+                r#type: event.kind,
+                attributes: event
+                    .attributes
+                    .into_iter()
+                    .map(|attribute| Attribute {
+                        key: attribute.key,
+                        value: attribute.value,
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        let data = Some(res.tx_result.data)
+            .filter(|data| !data.is_empty())
+            .map(|data| BASE64_STANDARD.encode(data));
+
+        let tx = decode_tx(&res.tx)
+            .map_err(|e| eprintln!("unexpected error when decode tx {} {}", res.hash, e))
+            .ok();
+
+        Transaction {
+            tx_hash: res.hash.to_string(),
+            events,
+            data,
+            tx,
+            height: Some(res.height.value()),
+        }
+    }
+}
+
 impl FromJsonValue for Transaction {
     fn try_from_value(value: serde_json::Value) -> anyhow::Result<Transaction> {
         let tx_hash = value
@@ -101,18 +589,40 @@ impl FromJsonValue for Transaction {
             _ => bail!("unexpected error missing result.events[tx.hash] is not string"),
         };
 
-        let events = value
+        let tx_result = value
             .get("result")
             .and_then(|v| v.get("data"))
             .and_then(|v| v.get("value"))
-            .and_then(|v| v.get("TxResult"))
-            .and_then(|v| v.get("result"))
-            .and_then(|v| v.get("events"))
-            .ok_or_else(|| {
-                anyhow!(
-                    "unexpected error missing result.data.value.TxResult.result.events attribute"
-                )
-            })?;
+            .and_then(|v| v.get("TxResult"));
+
+        // a tx that can not be decoded still has its events handled
+        let tx = tx_result
+            .and_then(|v| v.get("tx"))
+            .and_then(|v| v.as_str())
+            .and_then(|tx| BASE64_STANDARD.decode(tx).ok())
+            .and_then(|tx| {
+                decode_tx(&tx)
+                    .map_err(|e| eprintln!("unexpected error when decode tx {} {}", tx_hash, e))
+                    .ok()
+            });
+
+        let height = tx_result
+            .and_then(|v| v.get("height"))
+            .and_then(|v| match v {
+                Value::String(height) => height.parse().ok(),
+                height => height.as_u64(),
+            });
+
+        let tx_result = tx_result.and_then(|v| v.get("result"));
+
+        let data = tx_result
+            .and_then(|v| v.get("data"))
+            .and_then(|v| v.as_str())
+            .map(|data| data.to_owned());
+
+        let events = tx_result.and_then(|v| v.get("events")).ok_or_else(|| {
+            anyhow!("unexpected error missing result.data.value.TxResult.result.events attribute")
+        })?;
 
         let events = serde_json::from_value::<Vec<Event>>(events.to_owned())?
             .into_iter()
@@ -131,6 +641,9 @@ impl FromJsonValue for Transaction {
         Ok(Transaction {
             tx_hash: tx_hash.to_owned(),
             events,
+            data,
+            tx,
+            height,
         })
     }
 }
@@ -145,9 +658,7 @@ pub fn create_subcribe_message(query: Query) -> Message {
         }
     });
 
-    let msg = Message::text(msg.to_string());
-
-    msg
+    Message::text(msg.to_string())
 }
 
 fn to_utf8(base64: String) -> String {
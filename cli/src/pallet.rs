@@ -1,72 +1,129 @@
 use crate::{
-    find_attribute,
-    shared::{self, CreateActivityTransactionAndPointOnSaleParams},
-    to_utf8, Attribute, Event, Transaction,
+    block_time, events_caused_by, find_attribute, invalidate_nft_cache, mark_rarity_stale,
+    publish_market_event, received_by, retrieve_transfers,
+    shared::{
+        self, ComputeSaleFeesParams, CreateActivityTransactionAndPointOnSaleParams, ResolvedNft,
+    },
+    Attribute, Event, Transaction, Transfer,
 };
-use chrono::{DateTime, Utc};
+use chrono::DateTime;
 use database::{
     prelude::{DateTimeUtc, Decimal},
     repositories::{
-        self, nft::CreatePalletListingParams, nft_activity::CreateNftActivityParams,
-        tracing::CreateStreamTxParams, transaction::CreateTransactionParams,
+        self,
+        nft::{CreateNftOfferParams, CreatePalletListingParams},
+        nft_activity::CreateNftActivityParams,
+        tracing::CreateStreamTxParams,
+        transaction::CreateTransactionParams,
+        user_point::AwardUserPointParams,
     },
-    sea_orm_active_enums::{Marketplace, NftActivityKind, StreamContext},
-    DatabaseConnection, TransactionTrait,
+    sea_orm_active_enums::{LoyaltyPointKind, Marketplace, NftActivityKind, StreamContext},
+    DatabaseConnection, DatabaseTransaction, TransactionTrait,
+};
+use service::{
+    CacheConnection, CosmosClient, DecodedTx, ExecuteContractMsg, MarketEvent, MarketEventKind,
+    PalletListing, PALLET_CONTRACT_ADDRESS,
 };
-use service::{CosmosClient, PalletListing};
 use std::str::FromStr;
-use tendermint_rpc::endpoint::tx;
 
-static CREATE_AUCTION_ACTION: &'static str = "wasm-create_auction";
-static BUY_NOW_AUCTION: &'static str = "wasm-buy_now";
-static CANCEL_AUCTION: &'static str = "wasm-cancel_auction";
+static CREATE_AUCTION_ACTION: &str = "wasm-create_auction";
+static BUY_NOW_AUCTION: &str = "wasm-buy_now";
+static CANCEL_AUCTION: &str = "wasm-cancel_auction";
+static MAKE_OFFER: &str = "wasm-make_offer";
+static CANCEL_OFFER: &str = "wasm-cancel_offer";
+
+static BUY_NOW_MSG: &str = "buy_now";
+static MAKE_OFFER_MSG: &str = "make_offer";
+static CANCEL_OFFER_MSG: &str = "cancel_offer";
 
-pub async fn tx_handler(db: &DatabaseConnection, client: &CosmosClient, tx: Transaction) {
-    let Transaction { tx_hash, events } = tx;
+pub async fn tx_handler(
+    db: &DatabaseConnection,
+    client: &CosmosClient,
+    cache: &CacheConnection,
+    tx: Transaction,
+) -> bool {
+    let Transaction {
+        tx_hash,
+        events,
+        tx,
+        height,
+        ..
+    } = tx;
 
     let events = retrieve_pallet_events(events);
 
-    for event in events {
-        let action = &event.r#type;
-
-        let result = if action == CREATE_AUCTION_ACTION {
-            handle_create_auction(db, client, &event, &tx_hash).await
-        } else if action == BUY_NOW_AUCTION {
-            handle_buy_now(db, client, &event, &tx_hash).await
-        } else if action == CANCEL_AUCTION {
-            handle_cancel_auction(db, client, &event, &tx_hash).await
-        } else {
-            println!("unexpected action {} event {:#?}", action, event);
-            Ok(())
-        };
+    if events.is_empty() {
+        return true;
+    }
 
-        if let Err(error) = result {
-            repositories::tracing::create_stream_tx(
-                db,
-                CreateStreamTxParams {
-                    action: action.to_owned(),
-                    context: StreamContext::Pallet,
-                    date: Utc::now().into(),
-                    event: serde_json::json!(event),
-                    is_failure: true,
-                    tx_hash: tx_hash.to_owned(),
-                    message: Some(error.to_string()),
-                },
-            )
-            .await
-            .unwrap_or_else(|e| eprintln!("unexpected error when create tracing tx {}", e));
+    let date = match block_time(client, height).await {
+        Ok(date) => date,
+        Err(error) => {
+            eprintln!(
+                "unexpected error when get block time of pallet tx {} {}",
+                tx_hash, error
+            );
+            return false;
+        }
+    };
 
+    // remote data is resolved before the db transaction opens so it only spans the writes
+    let mut resolved = vec![];
+
+    for (event, transfers) in events {
+        match resolve(db, client, &event).await {
+            Ok(Some(remote)) => resolved.push((event, transfers, remote)),
+            Ok(None) => println!("unexpected action {} event {:#?}", event.r#type, event),
+            Err(error) => return record_failure(db, &event, &tx_hash, date, error).await,
+        }
+    }
+
+    // every event of the tx and its stream_tx record are written together or not at all
+    let txn = match db.begin().await {
+        Ok(txn) => txn,
+        Err(error) => {
             eprintln!(
-                "unexpected error when handle pallet event {} {} \n>>{}",
-                action, tx_hash, error
+                "unexpected error when begin db transaction {} {}",
+                tx_hash, error
             );
-        } else {
-            repositories::tracing::create_stream_tx(
-                db,
+            return false;
+        }
+    };
+
+    let mut touched = vec![];
+    let mut stale = vec![];
+    let mut market_events = vec![];
+
+    for (event, transfers, remote) in resolved {
+        let token = (
+            remote.nft().token_address.to_owned(),
+            remote.nft().token_id.to_owned(),
+        );
+        let created = remote.nft().is_new();
+
+        let result = match remote {
+            Remote::CreateAuction(nft, listing) => {
+                handle_create_auction(&txn, nft, listing, &tx_hash).await
+            }
+            Remote::BuyNow(nft) => {
+                handle_buy_now(&txn, nft, &tx_hash, date, tx.as_ref(), &transfers).await
+            }
+            Remote::CancelAuction(nft) => handle_cancel_auction(&txn, nft, &tx_hash, date).await,
+            Remote::MakeOffer(nft) => {
+                handle_make_offer(&txn, nft, &tx_hash, date, tx.as_ref()).await
+            }
+            Remote::CancelOffer(nft) => {
+                handle_cancel_offer(&txn, nft, &tx_hash, date, tx.as_ref()).await
+            }
+        };
+
+        let result = match result {
+            Ok(market_event) => repositories::tracing::create_stream_tx(
+                &txn,
                 CreateStreamTxParams {
-                    action: action.to_owned(),
+                    action: event.r#type.to_owned(),
                     context: StreamContext::Pallet,
-                    date: Utc::now().into(),
+                    date: date.into(),
                     event: serde_json::json!(event),
                     is_failure: false,
                     tx_hash: tx_hash.to_owned(),
@@ -74,40 +131,75 @@ pub async fn tx_handler(db: &DatabaseConnection, client: &CosmosClient, tx: Tran
                 },
             )
             .await
-            .unwrap_or_else(|e| eprintln!("unexpected error when create tracing tx {}", e));
+            .map(|_| market_event)
+            .map_err(anyhow::Error::from),
+            Err(error) => Err(error),
+        };
 
-            println!("done handle pallet event {} {}", action, tx_hash);
+        match result {
+            Ok(market_event) => market_events.extend(market_event),
+            Err(error) => {
+                txn.rollback()
+                    .await
+                    .unwrap_or_else(|e| eprintln!("unexpected error when rollback {}", e));
+
+                return record_failure(db, &event, &tx_hash, date, error).await;
+            }
+        }
+
+        if created {
+            stale.push(token.0.to_owned());
         }
+
+        touched.push(token);
     }
+
+    if let Err(error) = txn.commit().await {
+        eprintln!(
+            "unexpected error when commit pallet tx {} {}",
+            tx_hash, error
+        );
+        return false;
+    }
+
+    println!("done handle pallet tx {}", tx_hash);
+
+    for (token_address, token_id) in touched {
+        invalidate_nft_cache(cache, &token_address, &token_id).await;
+    }
+
+    stale.sort();
+    stale.dedup();
+
+    for collection_address in stale {
+        mark_rarity_stale(cache, &collection_address).await;
+    }
+
+    for market_event in market_events {
+        publish_market_event(cache, market_event).await;
+    }
+
+    true
 }
 
 async fn handle_create_auction(
-    db: &DatabaseConnection,
-    client: &CosmosClient,
-    event: &Event,
+    db: &DatabaseTransaction,
+    nft: ResolvedNft,
+    pallet_listing: PalletListing,
     tx_hash: &String,
-) -> anyhow::Result<()> {
-    let token_address = find_attribute(event, "collection_address")?;
-    let token_id = find_attribute(event, "token_id")?;
-
-    let nft_id = shared::create_nft_or_update_owner_or_just_find(
-        db,
-        client,
-        token_address.to_owned(),
-        token_id.to_owned(),
-        None,
-    )
-    .await?;
+) -> anyhow::Result<Option<MarketEvent>> {
+    let token_address = nft.token_address.to_owned();
+    let token_id = nft.token_id.to_owned();
 
-    let pallet_listing = client.get_pallet_listing(&token_address, &token_id).await?;
+    let nft_id = shared::create_nft_or_update_owner_or_just_find(db, nft, None).await?;
 
     let PalletListing { auction, owner } = pallet_listing;
 
     let Some(auction) = auction else {
-        return Ok(());
+        return Ok(None);
     };
 
-    let price = auction.prices.get(0).ok_or(anyhow::anyhow!(
+    let price = auction.prices.first().ok_or(anyhow::anyhow!(
         "unexpected error can not parse pallet listing price"
     ))?;
 
@@ -117,17 +209,15 @@ async fn handle_create_auction(
         anyhow::anyhow!("unexpected error can not parse pallet listing created_date"),
     )?;
 
-    let tx = db.begin().await?;
-
     repositories::nft::create_pallet_listing(
-        &tx,
+        db,
         CreatePalletListingParams {
             amount,
-            created_date: created_date.into(),
-            denom: "usei".to_string(),
+            created_date,
+            denom: price.denom.to_owned(),
             nft_id,
             tx_hash: tx_hash.to_owned(),
-            collection_address: token_address,
+            collection_address: token_address.to_owned(),
             expiration_time: Some(auction.expiration_time as i32),
             seller: owner.to_owned(),
         },
@@ -135,148 +225,443 @@ async fn handle_create_auction(
     .await?;
 
     repositories::nft_activity::create(
-        &tx,
+        db,
         CreateNftActivityParams {
             nft_id,
-            created_date: created_date.into(),
-            denom: "usei".to_string(),
+            created_date,
+            denom: price.denom.to_owned(),
             event_kind: NftActivityKind::List,
             marketplace: Marketplace::Pallet,
             metadata: serde_json::json!({}),
             price: amount,
-            seller_address: Some(owner),
+            seller_address: Some(owner.to_owned()),
             tx_hash: tx_hash.to_owned(),
             buyer_address: None,
         },
     )
     .await?;
 
-    tx.commit().await?;
+    // listing earns xp, the amount is decided by the loyalty rules
+    repositories::user_point::award(
+        db,
+        AwardUserPointParams {
+            date: created_date,
+            kind: LoyaltyPointKind::Xp,
+            wallet_address: owner.to_owned(),
+            price: amount,
+            denom: price.denom.to_owned(),
+            tx_hash: tx_hash.to_owned(),
+            collection_address: token_address.to_owned(),
+            marketplace: Marketplace::Pallet,
+        },
+    )
+    .await?;
+
+    repositories::collection_stats::refresh(db, &token_address).await?;
 
-    Ok(())
+    Ok(Some(MarketEvent {
+        kind: MarketEventKind::List,
+        collection_address: token_address,
+        token_id,
+        tx_hash: tx_hash.to_owned(),
+        marketplace: Some(Marketplace::Pallet.into()),
+        seller: Some(owner),
+        buyer: None,
+        price: Some(amount.to_string()),
+        denom: Some(price.denom.to_owned()),
+        date: created_date.timestamp(),
+    }))
 }
 
 async fn handle_buy_now(
-    db: &DatabaseConnection,
-    client: &CosmosClient,
-    event: &Event,
+    db: &DatabaseTransaction,
+    nft: ResolvedNft,
     tx_hash: &String,
-) -> anyhow::Result<()> {
-    let token_address = find_attribute(event, "collection_address")?;
-    let token_id = find_attribute(event, "token_id")?;
+    date: DateTimeUtc,
+    tx: Option<&DecodedTx>,
+    transfers: &[Transfer],
+) -> anyhow::Result<Option<MarketEvent>> {
+    let token_address = nft.token_address.to_owned();
+    let token_id = nft.token_id.to_owned();
 
-    let nft_id = shared::create_nft_or_update_owner_or_just_find(
-        db,
-        client,
-        token_address.to_owned(),
-        token_id,
-        None,
-    )
-    .await?;
+    let nft_id = shared::create_nft_or_update_owner_or_just_find(db, nft, None).await?;
 
     let db_listing = repositories::nft::find_listing_by_nft_id(db, nft_id).await?;
 
     let Some(db_listing) = db_listing else {
-        return Ok(());
+        return Ok(None);
     };
 
-    let tx = client.get_tx(&tx_hash).await?;
+    let purchase = find_pallet_msg(tx, BUY_NOW_MSG, &token_address, &token_id);
 
-    let buyer = find_buyer_address_from_tx(&tx).ok_or(anyhow::anyhow!(
-        "unexpected error can not get buyer from tx {} in buy now event",
-        tx_hash
-    ))?;
+    let buyer = purchase
+        .map(|purchase| purchase.sender.to_owned())
+        .or_else(|| tx.and_then(|tx| tx.signer.to_owned()))
+        .ok_or(anyhow::anyhow!(
+            "unexpected error can not get buyer from tx {} in buy now event",
+            tx_hash
+        ))?;
 
-    let db = db.begin().await?;
+    let paid = purchase.and_then(|purchase| purchase.funds_of(&db_listing.denom));
 
-    repositories::nft::delete_listing_if_exist(&db, nft_id).await?;
+    let fees = shared::compute_sale_fees(
+        db,
+        ComputeSaleFeesParams {
+            collection_address: &token_address,
+            marketplace: &Marketplace::Pallet,
+            price: db_listing.price,
+            proceeds: received_by(transfers, &db_listing.seller_address, &db_listing.denom),
+        },
+    )
+    .await?;
+
+    repositories::nft::delete_listing_if_exist(db, nft_id).await?;
 
     shared::create_activity_transaction_and_point_on_sale(
-        &db,
+        db,
         CreateActivityTransactionAndPointOnSaleParams {
-            buyer,
-            collection_address: token_address,
-            date: Utc::now().into(),
-            denom: "usei".to_string(),
+            buyer: buyer.to_owned(),
+            collection_address: token_address.to_owned(),
+            date,
+            denom: db_listing.denom.to_owned(),
             marketplace: Marketplace::Pallet,
-            metadata: serde_json::json!({}),
+            metadata: serde_json::json!({ "paid": paid }),
             nft_id,
             price: db_listing.price.to_string(),
-            seller: db_listing.seller_address,
+            seller: db_listing.seller_address.to_owned(),
             tx_hash: tx_hash.to_owned(),
+            fees,
         },
     )
     .await?;
 
-    Ok(())
+    repositories::collection_stats::refresh(db, &token_address).await?;
+
+    Ok(Some(MarketEvent {
+        kind: MarketEventKind::Sale,
+        collection_address: token_address,
+        token_id,
+        tx_hash: tx_hash.to_owned(),
+        marketplace: Some(Marketplace::Pallet.into()),
+        seller: Some(db_listing.seller_address),
+        buyer: Some(buyer),
+        price: Some(db_listing.price.to_string()),
+        denom: Some(db_listing.denom),
+        date: date.timestamp(),
+    }))
 }
 
 async fn handle_cancel_auction(
-    db: &DatabaseConnection,
-    client: &CosmosClient,
-    event: &Event,
+    db: &DatabaseTransaction,
+    nft: ResolvedNft,
     tx_hash: &String,
-) -> anyhow::Result<()> {
-    let token_address = find_attribute(event, "collection_address")?;
-    let token_id = find_attribute(event, "token_id")?;
+    date: DateTimeUtc,
+) -> anyhow::Result<Option<MarketEvent>> {
+    let token_address = nft.token_address.to_owned();
+    let token_id = nft.token_id.to_owned();
 
-    let nft_id =
-        shared::create_nft_or_update_owner_or_just_find(db, client, token_address, token_id, None)
-            .await?;
+    let nft_id = shared::create_nft_or_update_owner_or_just_find(db, nft, None).await?;
 
     let db_listing = repositories::nft::find_listing_by_nft_id(db, nft_id).await?;
 
     let Some(db_listing) = db_listing else {
-        return Ok(());
+        return Ok(None);
     };
 
-    let tx = db.begin().await?;
-
-    repositories::nft::delete_listing_if_exist(&tx, nft_id).await?;
+    repositories::nft::delete_listing_if_exist(db, nft_id).await?;
 
     repositories::nft_activity::create(
-        &tx,
+        db,
         CreateNftActivityParams {
             buyer_address: None,
-            created_date: Utc::now().into(),
-            denom: "usei".to_string(),
+            created_date: date,
+            denom: db_listing.denom.to_owned(),
             event_kind: NftActivityKind::Delist,
             marketplace: Marketplace::Pallet,
             metadata: serde_json::json!({}),
             nft_id,
             price: db_listing.price,
-            seller_address: Some(db_listing.seller_address),
+            seller_address: Some(db_listing.seller_address.to_owned()),
             tx_hash: tx_hash.to_owned(),
         },
     )
     .await?;
 
-    tx.commit().await?;
+    repositories::collection_stats::refresh(db, &token_address).await?;
 
-    Ok(())
+    Ok(Some(MarketEvent {
+        kind: MarketEventKind::Delist,
+        collection_address: token_address,
+        token_id,
+        tx_hash: tx_hash.to_owned(),
+        marketplace: Some(Marketplace::Pallet.into()),
+        seller: Some(db_listing.seller_address),
+        buyer: None,
+        price: Some(db_listing.price.to_string()),
+        denom: Some(db_listing.denom),
+        date: date.timestamp(),
+    }))
 }
 
-fn find_buyer_address_from_tx(tx: &tx::Response) -> Option<String> {
-    tx.tx_result
-        .events
-        .iter()
-        .find_map(|e| {
-            if e.kind != "wasm" {
-                None
-            } else {
-                e.attributes
-                    .iter()
-                    .find(|attribute| to_utf8(attribute.key.to_owned()) == "recipent")
-            }
-        })
-        .map(|attribute| attribute.value.to_owned())
+// the offered funds are escrowed with the make_offer msg
+async fn handle_make_offer(
+    db: &DatabaseTransaction,
+    nft: ResolvedNft,
+    tx_hash: &String,
+    date: DateTimeUtc,
+    tx: Option<&DecodedTx>,
+) -> anyhow::Result<Option<MarketEvent>> {
+    let token_address = nft.token_address.to_owned();
+    let token_id = nft.token_id.to_owned();
+
+    let offer =
+        find_pallet_msg(tx, MAKE_OFFER_MSG, &token_address, &token_id).ok_or(anyhow::anyhow!(
+            "unexpected error can not find make offer msg in tx {}",
+            tx_hash
+        ))?;
+
+    let price = offer.funds.first().ok_or(anyhow::anyhow!(
+        "unexpected error can not parse pallet offer price"
+    ))?;
+
+    let amount = Decimal::from_str(&price.amount)?;
+
+    let end_date = offer.msg[MAKE_OFFER_MSG]["expiration_time"]
+        .as_i64()
+        .and_then(|expiration_time| DateTime::from_timestamp(expiration_time, 0))
+        .ok_or(anyhow::anyhow!(
+            "unexpected error can not parse pallet offer expiration_time"
+        ))?;
+
+    let nft_id = shared::create_nft_or_update_owner_or_just_find(db, nft, None).await?;
+
+    repositories::nft::create_offer(
+        db,
+        CreateNftOfferParams {
+            nft_id,
+            tx_hash: tx_hash.to_owned(),
+            created_date: date,
+            end_date,
+            price: amount,
+            denom: price.denom.to_owned(),
+            buyer_address: offer.sender.to_owned(),
+        },
+    )
+    .await?;
+
+    repositories::nft_activity::create(
+        db,
+        CreateNftActivityParams {
+            nft_id,
+            created_date: date,
+            denom: price.denom.to_owned(),
+            event_kind: NftActivityKind::MakeOffer,
+            marketplace: Marketplace::Pallet,
+            metadata: serde_json::json!({}),
+            price: amount,
+            seller_address: None,
+            tx_hash: tx_hash.to_owned(),
+            buyer_address: Some(offer.sender.to_owned()),
+        },
+    )
+    .await?;
+
+    // revoked if the offer is cancelled, a bid can not be placed and pulled for points
+    repositories::user_point::award(
+        db,
+        AwardUserPointParams {
+            date,
+            kind: LoyaltyPointKind::Bid,
+            wallet_address: offer.sender.to_owned(),
+            price: amount,
+            denom: price.denom.to_owned(),
+            tx_hash: tx_hash.to_owned(),
+            collection_address: token_address.to_owned(),
+            marketplace: Marketplace::Pallet,
+        },
+    )
+    .await?;
+
+    Ok(Some(MarketEvent {
+        kind: MarketEventKind::Offer,
+        collection_address: token_address,
+        token_id,
+        tx_hash: tx_hash.to_owned(),
+        marketplace: Some(Marketplace::Pallet.into()),
+        seller: None,
+        buyer: Some(offer.sender.to_owned()),
+        price: Some(amount.to_string()),
+        denom: Some(price.denom.to_owned()),
+        date: date.timestamp(),
+    }))
+}
+
+async fn handle_cancel_offer(
+    db: &DatabaseTransaction,
+    nft: ResolvedNft,
+    tx_hash: &String,
+    date: DateTimeUtc,
+    tx: Option<&DecodedTx>,
+) -> anyhow::Result<Option<MarketEvent>> {
+    let token_address = nft.token_address.to_owned();
+    let token_id = nft.token_id.to_owned();
+
+    let buyer = find_pallet_msg(tx, CANCEL_OFFER_MSG, &token_address, &token_id)
+        .map(|cancel| cancel.sender.to_owned())
+        .ok_or(anyhow::anyhow!(
+            "unexpected error can not find cancel offer msg in tx {}",
+            tx_hash
+        ))?;
+
+    let nft_id = shared::create_nft_or_update_owner_or_just_find(db, nft, None).await?;
+
+    let offers = repositories::nft::delete_offers_of_buyer(db, nft_id, &buyer).await?;
+
+    for offer in offers {
+        repositories::nft_activity::create(
+            db,
+            CreateNftActivityParams {
+                nft_id,
+                created_date: date,
+                denom: offer.denom.to_owned(),
+                event_kind: NftActivityKind::CancelOffer,
+                marketplace: Marketplace::Pallet,
+                metadata: serde_json::json!({}),
+                price: offer.price,
+                seller_address: None,
+                tx_hash: tx_hash.to_owned(),
+                buyer_address: Some(buyer.to_owned()),
+            },
+        )
+        .await?;
+
+        repositories::user_point::revoke(db, LoyaltyPointKind::Bid, &buyer, &offer.tx_hash).await?;
+    }
+
+    Ok(None)
+}
+
+// remote data of a pallet event
+enum Remote {
+    CreateAuction(ResolvedNft, PalletListing),
+    BuyNow(ResolvedNft),
+    CancelAuction(ResolvedNft),
+    MakeOffer(ResolvedNft),
+    CancelOffer(ResolvedNft),
+}
+
+impl Remote {
+    fn nft(&self) -> &ResolvedNft {
+        match self {
+            Remote::CreateAuction(nft, _)
+            | Remote::BuyNow(nft)
+            | Remote::CancelAuction(nft)
+            | Remote::MakeOffer(nft)
+            | Remote::CancelOffer(nft) => nft,
+        }
+    }
+}
+
+async fn resolve(
+    db: &DatabaseConnection,
+    client: &CosmosClient,
+    event: &Event,
+) -> anyhow::Result<Option<Remote>> {
+    let token_address = find_attribute(event, "collection_address")?;
+    let token_id = find_attribute(event, "token_id")?;
+
+    let action = &event.r#type;
+
+    if action == CREATE_AUCTION_ACTION {
+        let listing = client.get_pallet_listing(&token_address, &token_id).await?;
+        let nft = shared::resolve_nft(db, client, token_address, token_id).await?;
+
+        Ok(Some(Remote::CreateAuction(nft, listing)))
+    } else if action == BUY_NOW_AUCTION {
+        let nft = shared::resolve_nft(db, client, token_address, token_id).await?;
+
+        Ok(Some(Remote::BuyNow(nft)))
+    } else if action == CANCEL_AUCTION {
+        let nft = shared::resolve_nft(db, client, token_address, token_id).await?;
+
+        Ok(Some(Remote::CancelAuction(nft)))
+    } else if action == MAKE_OFFER {
+        let nft = shared::resolve_nft(db, client, token_address, token_id).await?;
+
+        Ok(Some(Remote::MakeOffer(nft)))
+    } else if action == CANCEL_OFFER {
+        let nft = shared::resolve_nft(db, client, token_address, token_id).await?;
+
+        Ok(Some(Remote::CancelOffer(nft)))
+    } else {
+        Ok(None)
+    }
+}
+
+// a recorded failure counts as handled, the checkpoint moves past it and the tx is not retried on
+// every restart
+async fn record_failure(
+    db: &DatabaseConnection,
+    event: &Event,
+    tx_hash: &str,
+    date: DateTimeUtc,
+    error: anyhow::Error,
+) -> bool {
+    eprintln!(
+        "unexpected error when handle pallet event {} {} \n>>{}",
+        event.r#type, tx_hash, error
+    );
+
+    repositories::tracing::create_stream_tx(
+        db,
+        CreateStreamTxParams {
+            action: event.r#type.to_owned(),
+            context: StreamContext::Pallet,
+            date: date.into(),
+            event: serde_json::json!(event),
+            is_failure: true,
+            tx_hash: tx_hash.to_owned(),
+            message: Some(error.to_string()),
+        },
+    )
+    .await
+    .map_err(|e| eprintln!("unexpected error when create tracing tx {}", e))
+    .is_ok()
+}
+
+// a tx can buy or bid on several nfts, the msg for this one names it
+fn find_pallet_msg<'r>(
+    tx: Option<&'r DecodedTx>,
+    action: &str,
+    token_address: &str,
+    token_id: &str,
+) -> Option<&'r ExecuteContractMsg> {
+    tx?.messages.iter().find(|message| {
+        message.contract == PALLET_CONTRACT_ADDRESS
+            && message.names_nft(action, token_address, token_id)
+    })
 }
 
-fn retrieve_pallet_events(events: Vec<Event>) -> Vec<Event> {
+// each event comes with the transfers it caused, a sale only accounts for its own payouts
+fn retrieve_pallet_events(events: Vec<Event>) -> Vec<(Event, Vec<Transfer>)> {
+    let transfers = (0..events.len())
+        .map(|index| match is_pallet_event(&events[index]) {
+            true => retrieve_transfers(events_caused_by(&events, index)),
+            false => vec![],
+        })
+        .collect::<Vec<_>>();
+
     events
         .into_iter()
-        .filter(|Event { r#type, .. }| {
-            r#type == BUY_NOW_AUCTION || r#type == CANCEL_AUCTION || r#type == CREATE_AUCTION_ACTION
-        })
+        .zip(transfers)
+        .filter(|(event, _)| is_pallet_event(event))
         .collect()
 }
+
+fn is_pallet_event(Event { r#type, .. }: &Event) -> bool {
+    r#type == BUY_NOW_AUCTION
+        || r#type == CANCEL_AUCTION
+        || r#type == CREATE_AUCTION_ACTION
+        || r#type == MAKE_OFFER
+        || r#type == CANCEL_OFFER
+}
@@ -0,0 +1,89 @@
+use database::{repositories, ConnectOptions, Database, DatabaseConnection};
+use service::{
+    connect_cache, invalidate_collection_nfts, mark_rarity_stale, take_stale_rarity,
+    CacheConnection,
+};
+use std::time::Duration;
+
+static ALL_ARG: &str = "--all";
+
+// recompute rarity of the given collections (or of every collection with --all) once, otherwise
+// recompute the collections the streams marked stale on a schedule
+#[tokio::main]
+async fn main() {
+    dotenv::dotenv().ok();
+    let db_url = std::env::var("DATABASE_URL").expect("db_url must be set");
+    let redis_url = std::env::var("REDIS_URL").unwrap_or("redis://127.0.0.1/".to_owned());
+    let interval = std::env::var("RARITY_REFRESH_INTERVAL")
+        .ok()
+        .and_then(|interval| interval.parse::<u64>().ok())
+        .unwrap_or(300);
+
+    let mut opt = ConnectOptions::new(db_url);
+    opt.sqlx_logging(false);
+
+    let db = Database::connect(opt).await.unwrap();
+    let mut cache = connect_cache(&redis_url).await.unwrap();
+
+    let mut addresses = std::env::args().skip(1).collect::<Vec<String>>();
+
+    if addresses.iter().any(|address| address == ALL_ARG) {
+        addresses = repositories::collection::find_addresses(&db).await.unwrap();
+    }
+
+    if !addresses.is_empty() {
+        for address in addresses {
+            refresh_rarity(&db, &mut cache, &address).await;
+        }
+
+        return;
+    }
+
+    let mut interval = tokio::time::interval(Duration::from_secs(interval));
+
+    loop {
+        interval.tick().await;
+
+        let addresses = match take_stale_rarity(&mut cache).await {
+            Ok(addresses) => addresses,
+            Err(error) => {
+                eprintln!("unexpected error when take stale collections {}", error);
+                continue;
+            }
+        };
+
+        for address in addresses {
+            if !refresh_rarity(&db, &mut cache, &address).await {
+                // kept for the next run
+                mark_rarity_stale(&mut cache, &address)
+                    .await
+                    .unwrap_or_else(|e| eprintln!("unexpected error when mark rarity stale {}", e));
+            }
+        }
+    }
+}
+
+async fn refresh_rarity(
+    db: &DatabaseConnection,
+    cache: &mut CacheConnection,
+    address: &str,
+) -> bool {
+    match repositories::rarity::refresh_collection(db, address).await {
+        Ok(count) => println!("done compute rarity of {} nfts in {}", count, address),
+        Err(error) => {
+            eprintln!(
+                "unexpected error when compute rarity of {} \n>>{}",
+                address, error
+            );
+            return false;
+        }
+    }
+
+    // cached details still carry the previous ranks
+    invalidate_collection_nfts(cache, address)
+        .await
+        .map(|_| ())
+        .unwrap_or_else(|e| eprintln!("unexpected error when invalidate nft cache {}", e));
+
+    true
+}
@@ -0,0 +1,92 @@
+use cli::{shared::find_royalty, RPC_URL};
+use database::{repositories, ConnectOptions, Database, DatabaseConnection};
+use service::CosmosClient;
+use std::time::Duration;
+
+// re-read royalties of the given collections once, or of every collection on a schedule when none
+// is given, contracts can change their royalty at any time
+#[tokio::main]
+async fn main() {
+    dotenv::dotenv().ok();
+    let db_url = std::env::var("DATABASE_URL").expect("db_url must be set");
+    let interval = std::env::var("ROYALTY_REFRESH_INTERVAL")
+        .ok()
+        .and_then(|interval| interval.parse::<u64>().ok())
+        .unwrap_or(86400);
+
+    let mut opt = ConnectOptions::new(db_url);
+    opt.sqlx_logging(false);
+
+    let db = Database::connect(opt).await.unwrap();
+    let client = CosmosClient::from(tendermint_rpc::HttpClient::new(RPC_URL).unwrap());
+
+    let addresses = std::env::args().skip(1).collect::<Vec<String>>();
+
+    if !addresses.is_empty() {
+        for address in addresses {
+            refresh_royalty(&db, &client, &address).await;
+        }
+
+        return;
+    }
+
+    let mut interval = tokio::time::interval(Duration::from_secs(interval));
+
+    loop {
+        interval.tick().await;
+
+        let addresses = match repositories::collection::find_addresses(&db).await {
+            Ok(addresses) => addresses,
+            Err(error) => {
+                eprintln!("unexpected error when find collections {}", error);
+                continue;
+            }
+        };
+
+        for address in addresses {
+            refresh_royalty(&db, &client, &address).await;
+        }
+    }
+}
+
+async fn refresh_royalty(db: &DatabaseConnection, client: &CosmosClient, address: &str) {
+    let token_id = match repositories::nft::find_first_token_id(db, address).await {
+        Ok(Some(token_id)) => token_id,
+        Ok(None) => {
+            println!("skip {} without indexed nft", address);
+            return;
+        }
+        Err(error) => {
+            eprintln!(
+                "unexpected error when find nft of {} \n>>{}",
+                address, error
+            );
+            return;
+        }
+    };
+
+    let fallback = match client.get_nft_info(address, &token_id).await {
+        Ok(info) => info
+            .extension
+            .map(|extension| extension.royalty_percentage.unwrap_or_default()),
+        Err(error) => {
+            eprintln!(
+                "unexpected error when query nft of {} \n>>{}",
+                address, error
+            );
+            return;
+        }
+    };
+
+    let royalty = find_royalty(client, address, &token_id, fallback).await;
+
+    match repositories::collection::update_royalty(db, address, royalty.percentage, royalty.address)
+        .await
+    {
+        Ok(_) => println!("done refresh royalty of {}", address),
+        Err(error) => eprintln!(
+            "unexpected error when refresh royalty of {} \n>>{}",
+            address, error
+        ),
+    }
+}
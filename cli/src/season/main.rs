@@ -0,0 +1,159 @@
+use anyhow::{anyhow, bail};
+use chrono::{DateTime, Utc};
+use database::{
+    entities::loyalty_season_snapshot,
+    repositories::{self, loyalty_season::CreateLoyaltySeasonParams},
+    ConnectOptions, Database, DatabaseConnection,
+};
+use std::io::Write;
+
+static USAGE: &str = "usage:
+    season list
+    season create <name> <start rfc3339> <end rfc3339>
+    season snapshot <season id>
+    season export <season id> <csv|json> [output file]";
+
+// admin tool of the loyalty seasons, the snapshot taken here is what airdrops are paid from
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    dotenv::dotenv().ok();
+    let db_url = std::env::var("DATABASE_URL").expect("db_url must be set");
+
+    let mut opt = ConnectOptions::new(db_url);
+    opt.sqlx_logging(false);
+
+    let db = Database::connect(opt).await?;
+
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    let args = args.iter().map(String::as_str).collect::<Vec<&str>>();
+
+    match args.as_slice() {
+        ["list"] => list(&db).await,
+        ["create", name, start_date, end_date] => {
+            create(&db, name, parse_date(start_date)?, parse_date(end_date)?).await
+        }
+        ["snapshot", season_id] => snapshot(&db, season_id.parse()?).await,
+        ["export", season_id, format] => {
+            export(&db, season_id.parse()?, format, &mut std::io::stdout()).await
+        }
+        ["export", season_id, format, path] => {
+            export(
+                &db,
+                season_id.parse()?,
+                format,
+                &mut std::fs::File::create(path)?,
+            )
+            .await
+        }
+        _ => bail!(USAGE),
+    }
+}
+
+async fn list(db: &DatabaseConnection) -> anyhow::Result<()> {
+    for season in repositories::loyalty_season::find_all(db).await? {
+        println!(
+            "{}\t{}\t{} - {}\t{}",
+            season.id,
+            season.name,
+            season.start_date.to_rfc3339(),
+            season.end_date.to_rfc3339(),
+            season
+                .snapshot_date
+                .map(|date| format!("frozen at {}", date.to_rfc3339()))
+                .unwrap_or("running".into())
+        );
+    }
+
+    Ok(())
+}
+
+async fn create(
+    db: &DatabaseConnection,
+    name: &str,
+    start_date: DateTime<Utc>,
+    end_date: DateTime<Utc>,
+) -> anyhow::Result<()> {
+    if start_date >= end_date {
+        bail!("season must end after it starts");
+    }
+
+    let season = repositories::loyalty_season::create(
+        db,
+        CreateLoyaltySeasonParams {
+            name: name.to_owned(),
+            start_date,
+            end_date,
+        },
+    )
+    .await?;
+
+    println!("done create season {} {}", season.id, season.name);
+
+    Ok(())
+}
+
+async fn snapshot(db: &DatabaseConnection, season_id: i32) -> anyhow::Result<()> {
+    let season = repositories::loyalty_season::find_by_id(db, season_id)
+        .await?
+        .ok_or(anyhow!("season {} not found", season_id))?;
+
+    if season.end_date > Utc::now() {
+        bail!(
+            "season {} is still running until {}",
+            season_id,
+            season.end_date
+        );
+    }
+
+    match repositories::loyalty_season::take_snapshot(db, season_id).await? {
+        Some(count) => println!("done snapshot season {} with {} wallets", season_id, count),
+        None => bail!("season {} is already frozen", season_id),
+    }
+
+    Ok(())
+}
+
+async fn export(
+    db: &DatabaseConnection,
+    season_id: i32,
+    format: &str,
+    output: &mut impl Write,
+) -> anyhow::Result<()> {
+    let season = repositories::loyalty_season::find_by_id(db, season_id)
+        .await?
+        .ok_or(anyhow!("season {} not found", season_id))?;
+
+    if season.snapshot_date.is_none() {
+        bail!("season {} has no snapshot yet", season_id);
+    }
+
+    let entries = repositories::loyalty_season::find_full_snapshot(db, season_id).await?;
+
+    match format {
+        "csv" => {
+            writeln!(output, "rank,wallet_address,point")?;
+
+            for loyalty_season_snapshot::Model {
+                rank,
+                wallet_address,
+                point,
+                ..
+            } in entries
+            {
+                writeln!(output, "{},{},{}", rank, wallet_address, point)?;
+            }
+        }
+        "json" => serde_json::to_writer_pretty(&mut *output, &entries)?,
+        _ => bail!("unknown export format {}, expected csv or json", format),
+    }
+
+    output.flush()?;
+
+    Ok(())
+}
+
+fn parse_date(date: &str) -> anyhow::Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(date)
+        .map(|date| date.with_timezone(&Utc))
+        .map_err(|e| anyhow!("can not parse date {}, {}", date, e))
+}
@@ -4,90 +4,284 @@ use database::{
     prelude::{DateTimeUtc, Decimal},
     repositories::{
         collection::{self as CollectionRespository, CreateCollectionParams},
-        nft::{self as NftRepository, CreateNftParams},
+        config as ConfigRepository,
+        nft::{self as NftRepository, CreateNftParams, UpdateNftMetadataParams},
         nft_activity::{self as NftActivityRepository, CreateNftActivityParams},
         transaction::{self as TransactionRepository, CreateTransactionParams},
-        user_point::{self as UserPointRepository, CreateUserPointParams},
+        user_point::{self as UserPointRepository, AwardUserPointParams},
+        wash_trade as WashTradeRepository,
     },
     sea_orm_active_enums::{LoyaltyPointKind, Marketplace, NftActivityKind},
-    DatabaseConnection, DatabaseTransaction, DbErr, TransactionTrait,
+    ActiveEnum, ConnectionTrait, DatabaseTransaction,
 };
 use service::{get_collection_metadata, get_nft_metadata, CosmosClient};
 
-pub async fn create_collection_if_not_exist(
-    db: &DatabaseConnection,
+use crate::wash_trade_rules;
+
+// royalty_info is asked for this price so the returned amount reads as a percentage with 4 decimals
+static ROYALTY_SALE_PRICE: u128 = 1_000_000;
+
+pub struct Royalty {
+    pub percentage: Option<Decimal>,
+    pub address: Option<String>,
+}
+
+// the cw2981 extension wins when the contract implements it, the nft extension is the fallback
+pub async fn find_royalty(
+    client: &CosmosClient,
+    token_address: &str,
+    token_id: &str,
+    fallback: Option<f32>,
+) -> Royalty {
+    let fallback = Royalty {
+        percentage: fallback
+            .map(Decimal::from_f32_retain)
+            .map(Option::unwrap_or_default),
+        address: None,
+    };
+
+    // contracts without the extension reject the query
+    match client.check_cw2981_royalties(token_address).await {
+        Ok(check) if check.royalty_payments => {}
+        _ => return fallback,
+    }
+
+    let Ok(info) = client
+        .get_cw2981_royalty_info(token_address, token_id, ROYALTY_SALE_PRICE)
+        .await
+    else {
+        return fallback;
+    };
+
+    let Ok(amount) = Decimal::from_str(&info.royalty_amount) else {
+        return fallback;
+    };
+
+    Royalty {
+        percentage: Some(
+            (amount * Decimal::ONE_HUNDRED / Decimal::from(ROYALTY_SALE_PRICE)).round_dp(2),
+        ),
+        address: Some(info.address).filter(|address| !address.is_empty()),
+    }
+}
+
+// remote data of an nft, fetched before the db transaction of a tx is opened so the transaction
+// only spans the writes
+pub struct ResolvedNft {
+    pub token_address: String,
+    pub token_id: String,
+    // none when the nft was already indexed
+    created: Option<Box<NewNft>>,
+}
+
+struct NewNft {
+    nft: CreateNftParams,
+    collection: Option<CreateCollectionParams>,
+}
+
+impl ResolvedNft {
+    pub fn is_new(&self) -> bool {
+        self.created.is_some()
+    }
+}
+
+pub async fn resolve_collection(
+    db: &impl ConnectionTrait,
     client: &CosmosClient,
     address: String,
+    token_id: &str,
     royalty: Option<f32>,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<Option<CreateCollectionParams>> {
     let collection = CollectionRespository::find_by_address(db, &address).await?;
 
     if collection.is_some() {
-        return Ok(());
+        return Ok(None);
     }
 
     let metadata = get_collection_metadata(&address).await?;
     let supply = client.get_cw721_contract_supply(&address).await?;
     let info = client.get_cw721_contract_info(&address).await?;
+    let royalty = find_royalty(client, &address, token_id, royalty).await;
 
-    CollectionRespository::create(
-        db,
-        CreateCollectionParams {
-            address,
-            symbol: info.symbol,
-            name: info.name,
-            metadata,
-            supply: supply.count as i32,
-            royalty: royalty
-                .map(Decimal::from_f32_retain)
-                .map(Option::unwrap_or_default),
-        },
-    )
-    .await?;
-
-    Ok(())
+    Ok(Some(CreateCollectionParams {
+        address,
+        symbol: info.symbol,
+        name: info.name,
+        metadata,
+        supply: supply.count as i32,
+        royalty: royalty.percentage,
+        royalty_address: royalty.address,
+    }))
 }
 
-// only update owner from cw721 stream
-pub async fn create_nft_or_update_owner_or_just_find(
-    db: &DatabaseConnection,
+pub async fn resolve_nft(
+    db: &impl ConnectionTrait,
     client: &CosmosClient,
     token_address: String,
     token_id: String,
-    owner: Option<String>,
-) -> anyhow::Result<i32> {
+) -> anyhow::Result<ResolvedNft> {
     let nft = NftRepository::find_by_address_and_token_id(db, &token_address, &token_id).await?;
 
-    if let Some(nft) = nft {
-        NftRepository::update_owner(db, &token_address, &token_id, owner).await?;
-
-        return Ok(nft.id);
+    if nft.is_some() {
+        return Ok(ResolvedNft {
+            token_address,
+            token_id,
+            created: None,
+        });
     }
 
     let info = client.get_nft_info(&token_address, &token_id).await?;
 
     let metadata = get_nft_metadata(&info.token_uri).await?;
 
-    create_collection_if_not_exist(
+    let collection = resolve_collection(
         db,
         client,
         token_address.to_owned(),
+        &token_id,
         info.extension
             .map(|ex| ex.royalty_percentage.unwrap_or_default()),
     )
     .await?;
 
+    Ok(ResolvedNft {
+        created: Some(Box::new(NewNft {
+            nft: CreateNftParams {
+                token_address: token_address.to_owned(),
+                token_id: token_id.to_owned(),
+                token_uri: info.token_uri,
+                description: metadata.description,
+                image: metadata.image,
+                name: metadata.name,
+                owner_address: None,
+                traits: metadata.attributes,
+            },
+            collection,
+        })),
+        token_address,
+        token_id,
+    })
+}
+
+// native erc721s have no cw721 pointer, they are indexed under their evm address
+pub async fn resolve_erc721_nft(
+    db: &impl ConnectionTrait,
+    client: &CosmosClient,
+    token_address: String,
+    token_id: String,
+) -> anyhow::Result<ResolvedNft> {
+    let nft = NftRepository::find_by_address_and_token_id(db, &token_address, &token_id).await?;
+
+    if nft.is_some() {
+        return Ok(ResolvedNft {
+            token_address,
+            token_id,
+            created: None,
+        });
+    }
+
+    let token_uri = client
+        .get_erc721_token_uri(&token_address, &token_id)
+        .await?;
+
+    let metadata = get_nft_metadata(&token_uri).await?;
+
+    let collection = match CollectionRespository::find_by_address(db, &token_address).await? {
+        Some(_) => None,
+        None => {
+            let info = client.get_erc721_contract_info(&token_address).await?;
+            let supply = client
+                .get_erc721_contract_supply(&token_address)
+                .await
+                .map(|supply| supply.count)
+                .unwrap_or_default();
+
+            Some(CreateCollectionParams {
+                address: token_address.to_owned(),
+                symbol: info.symbol,
+                name: info.name,
+                metadata: get_collection_metadata(&token_address).await?,
+                supply: supply as i32,
+                royalty: None,
+                royalty_address: None,
+            })
+        }
+    };
+
+    Ok(ResolvedNft {
+        created: Some(Box::new(NewNft {
+            nft: CreateNftParams {
+                token_address: token_address.to_owned(),
+                token_id: token_id.to_owned(),
+                token_uri,
+                description: metadata.description,
+                image: metadata.image,
+                name: metadata.name,
+                owner_address: None,
+                traits: metadata.attributes,
+            },
+            collection,
+        })),
+        token_address,
+        token_id,
+    })
+}
+
+pub async fn resolve_nft_metadata(
+    client: &CosmosClient,
+    token_address: String,
+    token_id: String,
+) -> anyhow::Result<UpdateNftMetadataParams> {
+    let info = client.get_nft_info(&token_address, &token_id).await?;
+
+    let metadata = get_nft_metadata(&info.token_uri).await?;
+
+    Ok(UpdateNftMetadataParams {
+        token_address,
+        token_id,
+        token_uri: info.token_uri,
+        description: metadata.description,
+        image: metadata.image,
+        name: metadata.name,
+        traits: metadata.attributes,
+    })
+}
+
+// only update owner from cw721 stream
+pub async fn create_nft_or_update_owner_or_just_find(
+    db: &DatabaseTransaction,
+    nft: ResolvedNft,
+    owner: Option<String>,
+) -> anyhow::Result<i32> {
+    let ResolvedNft {
+        token_address,
+        token_id,
+        created,
+    } = nft;
+
+    let nft = NftRepository::find_by_address_and_token_id(db, &token_address, &token_id).await?;
+
+    if let Some(nft) = nft {
+        NftRepository::update_owner(db, &token_address, &token_id, owner).await?;
+
+        return Ok(nft.id);
+    }
+
+    let NewNft { nft, collection } = *created.ok_or(anyhow::anyhow!(
+        "unexpected error nft {} {} was not resolved",
+        token_address,
+        token_id
+    ))?;
+
+    if let Some(collection) = collection {
+        CollectionRespository::create(db, collection).await?;
+    }
+
     let nft_id = NftRepository::create(
         db,
         CreateNftParams {
-            token_address,
-            token_id,
-            token_uri: info.token_uri,
-            description: metadata.description,
-            image: metadata.image,
-            name: metadata.name,
             owner_address: owner,
-            traits: metadata.attributes,
+            ..nft
         },
     )
     .await?;
@@ -95,20 +289,63 @@ pub async fn create_nft_or_update_owner_or_just_find(
     Ok(nft_id)
 }
 
+// the seller proceeds found in the tx tell what was deducted, the collection rate tells how much of
+// it is royalty; without proceeds both are estimated from the configured rates
+pub async fn compute_sale_fees(
+    db: &impl ConnectionTrait,
+    params: ComputeSaleFeesParams<'_>,
+) -> anyhow::Result<SaleFees> {
+    let ComputeSaleFeesParams {
+        collection_address,
+        marketplace,
+        price,
+        proceeds,
+    } = params;
+
+    let royalty_percent = CollectionRespository::find_by_address(db, collection_address)
+        .await?
+        .and_then(|collection| collection.royalty)
+        .unwrap_or_default();
+
+    let royalty = price * royalty_percent / Decimal::ONE_HUNDRED;
+
+    if !proceeds.is_zero() && proceeds <= price {
+        let deducted = price - proceeds;
+        let royalty = royalty.min(deducted);
+
+        return Ok(SaleFees {
+            royalty_amount: royalty.round_dp(2),
+            marketplace_fee: (deducted - royalty).round_dp(2),
+        });
+    }
+
+    let fee_percent = ConfigRepository::find_value(
+        db,
+        &format!("marketplace_fee_percent:{}", marketplace.to_value()),
+    )
+    .await?
+    .and_then(|value| Decimal::from_str(&value).ok())
+    .unwrap_or_default();
+
+    Ok(SaleFees {
+        royalty_amount: royalty.round_dp(2),
+        marketplace_fee: (price * fee_percent / Decimal::ONE_HUNDRED).round_dp(2),
+    })
+}
+
 pub async fn create_activity_transaction_and_point_on_sale(
     db: &DatabaseTransaction,
     params: CreateActivityTransactionAndPointOnSaleParams,
 ) -> anyhow::Result<&DatabaseTransaction> {
     let price = Decimal::from_str(&params.price)?;
-    let point = i32::from_str(&params.price).map(|p| p / 1_000_000)?;
 
     NftActivityRepository::create(
-        &db,
+        db,
         CreateNftActivityParams {
             buyer_address: Some(params.buyer.to_owned()),
             seller_address: Some(params.seller.to_owned()),
             created_date: params.date,
-            denom: params.denom,
+            denom: params.denom.to_owned(),
             event_kind: NftActivityKind::Sale,
             marketplace: params.marketplace.to_owned(),
             metadata: params.metadata,
@@ -119,42 +356,56 @@ pub async fn create_activity_transaction_and_point_on_sale(
     )
     .await?;
 
-    TransactionRepository::create(
-        &db,
+    let transaction_id = TransactionRepository::create(
+        db,
         CreateTransactionParams {
             buyer_address: params.buyer.to_owned(),
             seller_address: params.seller.to_owned(),
-            collection_address: params.collection_address,
+            collection_address: params.collection_address.to_owned(),
             created_date: params.date,
-            marketplace: params.marketplace,
-            tx_hash: params.tx_hash,
+            marketplace: params.marketplace.to_owned(),
+            tx_hash: params.tx_hash.to_owned(),
             volume: price,
+            denom: params.denom.to_owned(),
+            royalty_amount: params.fees.royalty_amount,
+            marketplace_fee: params.fees.marketplace_fee,
         },
     )
     .await?;
 
-    UserPointRepository::create(
-        &db,
-        CreateUserPointParams {
+    UserPointRepository::award(
+        db,
+        AwardUserPointParams {
             date: params.date,
             kind: LoyaltyPointKind::Buy,
-            point,
             wallet_address: params.buyer,
+            price,
+            denom: params.denom.to_owned(),
+            tx_hash: params.tx_hash.to_owned(),
+            collection_address: params.collection_address.to_owned(),
+            marketplace: params.marketplace.to_owned(),
         },
     )
     .await?;
 
-    UserPointRepository::create(
-        &db,
-        CreateUserPointParams {
+    UserPointRepository::award(
+        db,
+        AwardUserPointParams {
             date: params.date,
             kind: LoyaltyPointKind::Sell,
-            point,
             wallet_address: params.seller,
+            price,
+            denom: params.denom,
+            tx_hash: params.tx_hash,
+            collection_address: params.collection_address,
+            marketplace: params.marketplace,
         },
     )
     .await?;
 
+    // a suspicious sale loses the points just awarded, its collection stats are refreshed by the caller
+    WashTradeRepository::flag_suspicious(db, Some(transaction_id), &wash_trade_rules()).await?;
+
     Ok(db)
 }
 
@@ -169,4 +420,18 @@ pub struct CreateActivityTransactionAndPointOnSaleParams {
     pub collection_address: String,
     pub metadata: serde_json::Value,
     pub marketplace: Marketplace,
+    pub fees: SaleFees,
+}
+
+pub struct ComputeSaleFeesParams<'r> {
+    pub collection_address: &'r str,
+    pub marketplace: &'r Marketplace,
+    pub price: Decimal,
+    // what the seller received in the sale denom, zero when unknown
+    pub proceeds: Decimal,
+}
+
+pub struct SaleFees {
+    pub royalty_amount: Decimal,
+    pub marketplace_fee: Decimal,
 }
@@ -0,0 +1,57 @@
+use anyhow::bail;
+use chrono::{Duration, Utc};
+use cli::wash_trade_rules;
+use database::{repositories, ConnectOptions, Database, DatabaseConnection};
+
+static USAGE: &str = "usage:
+    wash-trade scan
+    wash-trade report [last hours]";
+
+// admin tool, `scan` re-checks every sale with the current rules and `report` lists what is flagged
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    dotenv::dotenv().ok();
+    let db_url = std::env::var("DATABASE_URL").expect("db_url must be set");
+
+    let mut opt = ConnectOptions::new(db_url);
+    opt.sqlx_logging(false);
+
+    let db = Database::connect(opt).await?;
+
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    let args = args.iter().map(String::as_str).collect::<Vec<&str>>();
+
+    match args.as_slice() {
+        ["scan"] => scan(&db).await,
+        ["report"] => report(&db, None).await,
+        ["report", hours] => report(&db, Some(hours.parse()?)).await,
+        _ => bail!(USAGE),
+    }
+}
+
+async fn scan(db: &DatabaseConnection) -> anyhow::Result<()> {
+    let collections =
+        repositories::wash_trade::flag_suspicious(db, None, &wash_trade_rules()).await?;
+
+    for collection_address in &collections {
+        repositories::collection_stats::refresh_one(db, collection_address).await?;
+    }
+
+    println!(
+        "done scan wash trades, {} collections had new flagged sales",
+        collections.len()
+    );
+
+    Ok(())
+}
+
+async fn report(db: &DatabaseConnection, hours: Option<i64>) -> anyhow::Result<()> {
+    let since = hours.map(|hours| Utc::now() - Duration::hours(hours));
+
+    let flagged = repositories::wash_trade::find_flagged(db, since).await?;
+
+    serde_json::to_writer_pretty(std::io::stdout(), &flagged)?;
+    println!();
+
+    Ok(())
+}
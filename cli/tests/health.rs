@@ -0,0 +1,38 @@
+use cli::health::StreamStatus;
+use database::sea_orm_active_enums::StreamContext;
+
+#[test]
+fn resumes_from_the_saved_checkpoint() {
+    let status = StreamStatus::new(StreamContext::Pallet, Some(100));
+
+    assert_eq!(status.checkpoint(), Some(100));
+    assert_eq!(
+        StreamStatus::new(StreamContext::Pallet, None).checkpoint(),
+        None
+    );
+}
+
+#[test]
+fn checkpoint_follows_the_highest_handled_tx() {
+    let status = StreamStatus::new(StreamContext::Evm, Some(100));
+
+    status.on_processed(Some(120));
+    status.on_processed(Some(110));
+    status.on_processed(None);
+
+    assert_eq!(status.last_height(), Some(120));
+    assert_eq!(status.checkpoint(), Some(120));
+}
+
+#[test]
+fn checkpoint_stays_below_an_unrecorded_failure() {
+    let status = StreamStatus::new(StreamContext::Cwr721, Some(100));
+
+    status.on_processed(Some(105));
+    status.on_failed(Some(108));
+    status.on_processed(Some(130));
+    status.on_failed(Some(112));
+
+    assert_eq!(status.last_height(), Some(130));
+    assert_eq!(status.checkpoint(), Some(107));
+}
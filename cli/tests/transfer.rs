@@ -0,0 +1,169 @@
+use std::str::FromStr;
+
+use cli::{events_caused_by, received_by, retrieve_transfers, Attribute, Event, Transfer};
+use database::prelude::Decimal;
+use service::PALLET_CONTRACT_ADDRESS as PALLET;
+
+static BUYER: &str = "sei1zjglfl958uhrjkvezpjnnlvk2vsyu5u93m8695";
+static SELLER: &str = "sei1hntfywgqqj95v9ur3dshzyd9dhe5wqfwm8xcn6";
+static OTHER_SELLER: &str = "sei1kt90gn6dschhhrgute4ufpx5akcenutd3305hn";
+static FEE_RECIPIENT: &str = "sei137agce30nm5jxxpvqccgc5c87ahqeuuppsywqg";
+
+static USDC: &str = "ibc/CA6FBFAF399474A06263E10D0CE5AEBBE15189D6D4B2DD9ADE61007E68EB9DB0";
+
+fn event(r#type: &str, attributes: &[(&str, &str)]) -> Event {
+    Event {
+        r#type: r#type.to_owned(),
+        attributes: attributes
+            .iter()
+            .map(|(key, value)| Attribute {
+                key: key.to_string(),
+                value: value.to_string(),
+            })
+            .collect(),
+    }
+}
+
+fn transfer(sender: &str, recipient: &str, amount: &str) -> Event {
+    event(
+        "transfer",
+        &[
+            ("recipient", recipient),
+            ("sender", sender),
+            ("amount", amount),
+        ],
+    )
+}
+
+fn buy_now(token_id: &str) -> Event {
+    event(
+        "wasm-buy_now",
+        &[("_contract_address", PALLET), ("token_id", token_id)],
+    )
+}
+
+fn message(action: &str) -> Event {
+    event("message", &[("action", action)])
+}
+
+fn decimal(value: &str) -> Decimal {
+    Decimal::from_str(value).unwrap()
+}
+
+fn amount(amount: &str) -> Transfer {
+    Transfer {
+        sender: BUYER.to_owned(),
+        recipient: SELLER.to_owned(),
+        amount: amount.to_owned(),
+    }
+}
+
+#[test]
+fn retrieves_every_transfer_of_an_event() {
+    let events = vec![
+        event(
+            "transfer",
+            &[
+                ("recipient", SELLER),
+                ("sender", BUYER),
+                ("amount", "95usei"),
+                ("recipient", FEE_RECIPIENT),
+                ("sender", BUYER),
+                ("amount", "5usei"),
+            ],
+        ),
+        event("coin_spent", &[("spender", BUYER), ("amount", "100usei")]),
+        transfer(PALLET, OTHER_SELLER, "10usei"),
+    ];
+
+    let transfers = retrieve_transfers(&events);
+
+    assert_eq!(transfers.len(), 3);
+    assert_eq!(transfers[0].recipient, SELLER);
+    assert_eq!(transfers[0].sender, BUYER);
+    assert_eq!(transfers[0].amount, "95usei");
+    assert_eq!(transfers[1].recipient, FEE_RECIPIENT);
+    assert_eq!(transfers[1].amount, "5usei");
+    assert_eq!(transfers[2].sender, PALLET);
+}
+
+#[test]
+fn ignores_attributes_before_a_recipient() {
+    let events = vec![event("transfer", &[("sender", BUYER), ("amount", "1usei")])];
+
+    assert!(retrieve_transfers(&events).is_empty());
+}
+
+#[test]
+fn amount_of_picks_the_denom() {
+    let transfer = amount(&format!("1500000usei,250{}", USDC));
+
+    assert_eq!(transfer.amount_of("usei"), decimal("1500000"));
+    assert_eq!(transfer.amount_of(USDC), decimal("250"));
+    assert_eq!(transfer.amount_of("uatom"), Decimal::ZERO);
+}
+
+#[test]
+fn amount_of_matches_whole_denoms() {
+    assert_eq!(amount("10useix").amount_of("usei"), Decimal::ZERO);
+    assert_eq!(amount("").amount_of("usei"), Decimal::ZERO);
+    assert_eq!(amount("usei").amount_of("usei"), Decimal::ZERO);
+}
+
+#[test]
+fn received_by_sums_the_wallet_transfers() {
+    let transfers = vec![
+        amount("40usei"),
+        amount("60usei,5uatom"),
+        Transfer {
+            recipient: FEE_RECIPIENT.to_owned(),
+            ..amount("100usei")
+        },
+    ];
+
+    assert_eq!(received_by(&transfers, SELLER, "usei"), decimal("100"));
+    assert_eq!(received_by(&transfers, SELLER, "uatom"), decimal("5"));
+    assert_eq!(received_by(&transfers, BUYER, "usei"), Decimal::ZERO);
+}
+
+#[test]
+fn scopes_transfers_to_their_sale() {
+    let events = vec![
+        message("/cosmwasm.wasm.v1.MsgExecuteContract"),
+        transfer(BUYER, PALLET, "200usei"),
+        buy_now("1"),
+        transfer(PALLET, SELLER, "95usei"),
+        event("message", &[("sender", PALLET)]),
+        transfer(PALLET, FEE_RECIPIENT, "5usei"),
+        buy_now("2"),
+        transfer(PALLET, OTHER_SELLER, "90usei"),
+        transfer(PALLET, SELLER, "10usei"),
+    ];
+
+    let first = retrieve_transfers(events_caused_by(&events, 2));
+    let second = retrieve_transfers(events_caused_by(&events, 6));
+
+    assert_eq!(first.len(), 2);
+    assert_eq!(received_by(&first, SELLER, "usei"), decimal("95"));
+    assert_eq!(received_by(&first, OTHER_SELLER, "usei"), Decimal::ZERO);
+
+    assert_eq!(second.len(), 2);
+    assert_eq!(received_by(&second, SELLER, "usei"), decimal("10"));
+    assert_eq!(received_by(&second, OTHER_SELLER, "usei"), decimal("90"));
+}
+
+#[test]
+fn stops_scope_at_the_next_message() {
+    let events = vec![
+        message("/cosmwasm.wasm.v1.MsgExecuteContract"),
+        buy_now("1"),
+        transfer(PALLET, SELLER, "95usei"),
+        message("/cosmos.bank.v1beta1.MsgSend"),
+        transfer(BUYER, SELLER, "1000usei"),
+    ];
+
+    let transfers = retrieve_transfers(events_caused_by(&events, 1));
+
+    assert_eq!(received_by(&transfers, SELLER, "usei"), decimal("95"));
+    assert!(events_caused_by(&events, events.len()).is_empty());
+}
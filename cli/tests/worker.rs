@@ -0,0 +1,158 @@
+use cli::{worker_of, Attribute, Event, Transaction};
+use service::PALLET_CONTRACT_ADDRESS as PALLET;
+
+static COLLECTION: &str = "sei1hcq8phzkarn6nr7wk3y5qwh7jyfzfckh9xdwj0rcpeagrdz7rrgsxs8jzv";
+
+const WORKERS: usize = 64;
+
+fn event(r#type: &str, attributes: &[(&str, &str)]) -> Event {
+    Event {
+        r#type: r#type.to_owned(),
+        attributes: attributes
+            .iter()
+            .map(|(key, value)| Attribute {
+                key: key.to_string(),
+                value: value.to_string(),
+            })
+            .collect(),
+    }
+}
+
+fn tx(tx_hash: &str, events: Vec<Event>) -> Transaction {
+    Transaction {
+        tx_hash: tx_hash.to_owned(),
+        events,
+        data: None,
+        tx: None,
+        height: None,
+    }
+}
+
+fn listing(collection: &str, token_id: &str) -> Event {
+    event(
+        "wasm-create_auction",
+        &[
+            ("_contract_address", PALLET),
+            ("collection_address", collection),
+            ("token_id", token_id),
+        ],
+    )
+}
+
+fn send_nft(collection: &str, token_id: &str) -> Event {
+    event(
+        "wasm",
+        &[
+            ("_contract_address", collection),
+            ("action", "send_nft"),
+            ("sender", "sei1zjglfl958uhrjkvezpjnnlvk2vsyu5u93m8695"),
+            ("recipient", PALLET),
+            ("token_id", token_id),
+        ],
+    )
+}
+
+// two collections ordered by different workers
+fn spread_collections() -> (String, String) {
+    let worker = |collection: &str| worker_of(&tx("A", vec![listing(collection, "1")]), WORKERS);
+
+    let collections = (0..WORKERS * 4)
+        .map(|index| format!("sei1collection{}", index))
+        .collect::<Vec<_>>();
+
+    let first = &collections[0];
+
+    let second = collections
+        .iter()
+        .find(|collection| worker(collection) != worker(first))
+        .expect("collections must spread over workers");
+
+    (first.to_owned(), second.to_owned())
+}
+
+#[test]
+fn marketplace_and_cw721_events_of_a_collection_share_a_worker() {
+    let list = tx(
+        "A",
+        vec![send_nft(COLLECTION, "1358"), listing(COLLECTION, "1358")],
+    );
+    let buy = tx(
+        "B",
+        vec![event(
+            "wasm-buy_now",
+            &[
+                ("_contract_address", PALLET),
+                ("collection_address", COLLECTION),
+                ("token_id", "1358"),
+            ],
+        )],
+    );
+    let transfer = tx("C", vec![send_nft(COLLECTION, "7")]);
+
+    let worker = worker_of(&list, WORKERS);
+
+    assert!(worker.is_some());
+    assert_eq!(worker_of(&buy, WORKERS), worker);
+    assert_eq!(worker_of(&transfer, WORKERS), worker);
+}
+
+#[test]
+fn contract_address_of_other_wasm_events_is_ignored() {
+    let listed = tx("A", vec![listing(COLLECTION, "1")]);
+    let listed_with_fee = tx(
+        "B",
+        vec![
+            listing(COLLECTION, "1"),
+            event(
+                "wasm",
+                &[
+                    ("_contract_address", PALLET),
+                    ("action", "pay_fee"),
+                    ("token_id", "1"),
+                ],
+            ),
+        ],
+    );
+
+    assert_eq!(
+        worker_of(&listed_with_fee, WORKERS),
+        worker_of(&listed, WORKERS)
+    );
+}
+
+#[test]
+fn tx_spanning_workers_has_no_worker() {
+    let (first, second) = spread_collections();
+
+    let batch = tx("A", vec![listing(&first, "1"), listing(&second, "1")]);
+
+    assert_eq!(worker_of(&batch, WORKERS), None);
+}
+
+#[test]
+fn several_nfts_of_one_collection_keep_its_worker() {
+    let (first, _) = spread_collections();
+
+    let batch = tx("A", vec![listing(&first, "1"), listing(&first, "2")]);
+
+    assert_eq!(
+        worker_of(&batch, WORKERS),
+        worker_of(&tx("B", vec![listing(&first, "3")]), WORKERS)
+    );
+}
+
+#[test]
+fn tx_without_collection_is_spread_by_hash() {
+    let unrelated = tx("A", vec![event("transfer", &[("recipient", PALLET)])]);
+
+    assert!(worker_of(&unrelated, WORKERS).is_some_and(|worker| worker < WORKERS));
+}
+
+#[test]
+fn single_worker_takes_every_tx() {
+    let (first, second) = spread_collections();
+
+    let batch = tx("A", vec![listing(&first, "1"), listing(&second, "1")]);
+
+    assert_eq!(worker_of(&batch, 1), Some(0));
+}
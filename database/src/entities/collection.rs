@@ -18,6 +18,7 @@ pub struct Model {
     pub supply: i32,
     #[sea_orm(column_type = "JsonBinary", nullable)]
     pub socials: Option<Json>,
+    pub royalty_address: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
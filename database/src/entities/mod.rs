@@ -5,10 +5,15 @@ pub mod prelude;
 pub mod collection;
 pub mod collection_offer;
 pub mod collection_snapshot;
+pub mod collection_stats;
 pub mod config;
+pub mod evm_contract;
 pub mod failure_stream_tx;
 pub mod launchpad_collection;
 pub mod listing_nft;
+pub mod loyalty_rule;
+pub mod loyalty_season;
+pub mod loyalty_season_snapshot;
 pub mod mint_group;
 pub mod mint_info;
 pub mod missing_stream_block;
@@ -18,7 +23,9 @@ pub mod nft_bidding;
 pub mod nft_offer;
 pub mod nft_trait;
 pub mod sea_orm_active_enums;
+pub mod stream_checkpoint;
 pub mod stream_tx;
 pub mod transaction;
+pub mod transaction_flag;
 pub mod user;
 pub mod user_loyalty_point;
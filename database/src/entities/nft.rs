@@ -3,7 +3,7 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "nft")]
 pub struct Model {
     #[sea_orm(primary_key)]
@@ -15,6 +15,11 @@ pub struct Model {
     pub image: Option<String>,
     pub description: Option<String>,
     pub owner_address: Option<String>,
+    #[sea_orm(column_type = "Decimal(Some((90, 4)))", nullable)]
+    pub rarity_score: Option<Decimal>,
+    #[sea_orm(column_type = "Double", nullable)]
+    pub statistical_rarity: Option<f64>,
+    pub rarity_rank: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -35,6 +40,8 @@ pub enum Relation {
     NftOffer,
     #[sea_orm(has_many = "super::nft_trait::Entity")]
     NftTrait,
+    #[sea_orm(has_many = "super::user::Entity")]
+    User,
 }
 
 impl Related<super::collection::Entity> for Entity {
@@ -67,4 +74,10 @@ impl Related<super::nft_trait::Entity> for Entity {
     }
 }
 
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
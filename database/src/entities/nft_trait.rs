@@ -11,6 +11,8 @@ pub struct Model {
     pub attribute: String,
     pub value: String,
     pub display_type: Option<String>,
+    #[sea_orm(column_type = "Decimal(Some((90, 8)))", nullable)]
+    pub numeric_value: Option<Decimal>,
     pub nft_id: i32,
 }
 
@@ -3,10 +3,15 @@
 pub use super::collection::Entity as Collection;
 pub use super::collection_offer::Entity as CollectionOffer;
 pub use super::collection_snapshot::Entity as CollectionSnapshot;
+pub use super::collection_stats::Entity as CollectionStats;
 pub use super::config::Entity as Config;
+pub use super::evm_contract::Entity as EvmContract;
 pub use super::failure_stream_tx::Entity as FailureStreamTx;
 pub use super::launchpad_collection::Entity as LaunchpadCollection;
 pub use super::listing_nft::Entity as ListingNft;
+pub use super::loyalty_rule::Entity as LoyaltyRule;
+pub use super::loyalty_season::Entity as LoyaltySeason;
+pub use super::loyalty_season_snapshot::Entity as LoyaltySeasonSnapshot;
 pub use super::mint_group::Entity as MintGroup;
 pub use super::mint_info::Entity as MintInfo;
 pub use super::missing_stream_block::Entity as MissingStreamBlock;
@@ -15,7 +20,9 @@ pub use super::nft_activity::Entity as NftActivity;
 pub use super::nft_bidding::Entity as NftBidding;
 pub use super::nft_offer::Entity as NftOffer;
 pub use super::nft_trait::Entity as NftTrait;
+pub use super::stream_checkpoint::Entity as StreamCheckpoint;
 pub use super::stream_tx::Entity as StreamTx;
 pub use super::transaction::Entity as Transaction;
+pub use super::transaction_flag::Entity as TransactionFlag;
 pub use super::user::Entity as User;
 pub use super::user_loyalty_point::Entity as UserLoyaltyPoint;
@@ -18,6 +18,8 @@ pub enum LoyaltyPointKind {
 #[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "marketplace")]
 pub enum Marketplace {
+    #[sea_orm(string_value = "evm")]
+    Evm,
     #[sea_orm(string_value = "mrkt")]
     Mrkt,
     #[sea_orm(string_value = "pallet")]
@@ -50,6 +52,8 @@ pub enum SaleType {
 pub enum StreamContext {
     #[sea_orm(string_value = "cwr721")]
     Cwr721,
+    #[sea_orm(string_value = "evm")]
+    Evm,
     #[sea_orm(string_value = "launchpad")]
     Launchpad,
     #[sea_orm(string_value = "mrkt")]
@@ -57,3 +61,13 @@ pub enum StreamContext {
     #[sea_orm(string_value = "pallet")]
     Pallet,
 }
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "wash_trade_reason")]
+pub enum WashTradeReason {
+    #[sea_orm(string_value = "circular_trade")]
+    CircularTrade,
+    #[sea_orm(string_value = "off_floor_price")]
+    OffFloorPrice,
+    #[sea_orm(string_value = "self_trade")]
+    SelfTrade,
+}
@@ -17,6 +17,12 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
     pub market: Marketplace,
+    pub is_flagged: bool,
+    #[sea_orm(column_type = "Decimal(Some((90, 2)))")]
+    pub royalty_amount: Decimal,
+    #[sea_orm(column_type = "Decimal(Some((90, 2)))")]
+    pub marketplace_fee: Decimal,
+    pub denom: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -29,6 +35,8 @@ pub enum Relation {
         on_delete = "Restrict"
     )]
     Collection,
+    #[sea_orm(has_many = "super::transaction_flag::Entity")]
+    TransactionFlag,
 }
 
 impl Related<super::collection::Entity> for Entity {
@@ -37,4 +45,10 @@ impl Related<super::collection::Entity> for Entity {
     }
 }
 
+impl Related<super::transaction_flag::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TransactionFlag.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -9,9 +9,29 @@ pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub address: String,
     pub is_new_user: bool,
+    pub display_name: Option<String>,
+    pub avatar_nft_id: Option<i32>,
+    pub notify_on_sale: bool,
+    pub notify_on_offer: bool,
+    pub notify_on_bid: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::nft::Entity",
+        from = "Column::AvatarNftId",
+        to = "super::nft::Column::Id",
+        on_update = "Cascade",
+        on_delete = "SetNull"
+    )]
+    Nft,
+}
+
+impl Related<super::nft::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Nft.def()
+    }
+}
 
 impl ActiveModelBehavior for ActiveModel {}
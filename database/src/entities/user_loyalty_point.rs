@@ -1,6 +1,7 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.15
 
 use super::sea_orm_active_enums::LoyaltyPointKind;
+use super::sea_orm_active_enums::Marketplace;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -13,9 +14,28 @@ pub struct Model {
     pub date: DateTimeWithTimeZone,
     pub kind: LoyaltyPointKind,
     pub point: i32,
+    pub tx_hash: Option<String>,
+    pub collection_address: Option<String>,
+    pub marketplace: Option<Marketplace>,
+    pub rule_id: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::loyalty_rule::Entity",
+        from = "Column::RuleId",
+        to = "super::loyalty_rule::Column::Id",
+        on_update = "Cascade",
+        on_delete = "SetNull"
+    )]
+    LoyaltyRule,
+}
+
+impl Related<super::loyalty_rule::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::LoyaltyRule.def()
+    }
+}
 
 impl ActiveModelBehavior for ActiveModel {}
@@ -1,6 +1,6 @@
 #![allow(unused_imports)]
 #![allow(dead_code)]
-mod entities;
+pub mod entities;
 use entities::prelude::*;
 
 pub use entities::sea_orm_active_enums;
@@ -9,7 +9,7 @@ pub use sea_orm::*;
 use serde::Deserialize;
 pub mod repositories;
 
-#[derive(ScribeStaticStr, Deserialize, Debug)]
+#[derive(ScribeStaticStr, Deserialize, Debug, Default)]
 pub enum Sort {
     #[enumscribe(str = "ASC")]
     #[serde(rename(deserialize = "asc"))]
@@ -17,11 +17,25 @@ pub enum Sort {
 
     #[enumscribe(str = "DESC")]
     #[serde(rename(deserialize = "desc"))]
+    #[default]
     Desc,
 }
 
-impl Default for Sort {
-    fn default() -> Self {
-        Self::Desc
+impl From<Sort> for sea_query::Order {
+    fn from(sort: Sort) -> Self {
+        match sort {
+            Sort::Asc => Self::Asc,
+            Sort::Desc => Self::Desc,
+        }
+    }
+}
+
+impl From<sea_orm_active_enums::Marketplace> for service::Marketplace {
+    fn from(marketplace: sea_orm_active_enums::Marketplace) -> Self {
+        match marketplace {
+            sea_orm_active_enums::Marketplace::Evm => Self::Evm,
+            sea_orm_active_enums::Marketplace::Mrkt => Self::Mrkt,
+            sea_orm_active_enums::Marketplace::Pallet => Self::Pallet,
+        }
     }
 }
@@ -1,23 +1,41 @@
 use enumscribe::ScribeStaticStr;
 use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::sea_query::{
+    Alias, Asterisk, Condition, Expr, Func, LikeExpr, NullOrdering, Query, SelectStatement,
+};
 use sea_orm::{
-    prelude::Decimal, sea_query::OnConflict, DatabaseConnection, DbErr, EntityTrait, Set,
+    prelude::Decimal, sea_query::OnConflict, ActiveValue::Unchanged, DatabaseConnection, DbErr,
+    EntityTrait, QuerySelect, Set,
 };
-use sea_orm::{query, ConnectionTrait, DatabaseBackend, FromQueryResult, Statement};
+use sea_orm::{ConnectionTrait, FromQueryResult};
 use serde::Serialize;
 use service::CollectionMetadata;
 
 use crate::entities::collection;
 use crate::{Collection, Sort};
 
+static COLLECTION_VIEW: &str = "collection_view";
+
 pub async fn find_by_address(
-    db: &DatabaseConnection,
+    db: &impl ConnectionTrait,
     address: &str,
 ) -> Result<Option<collection::Model>, DbErr> {
     Collection::find_by_id(address).one(db).await
 }
 
-pub async fn create(db: &DatabaseConnection, params: CreateCollectionParams) -> Result<(), DbErr> {
+pub async fn find_addresses(db: &DatabaseConnection) -> Result<Vec<String>, DbErr> {
+    Collection::find()
+        .select_only()
+        .column(collection::Column::Address)
+        .into_tuple()
+        .all(db)
+        .await
+}
+
+pub async fn create(
+    db: &impl ConnectionTrait,
+    params: CreateCollectionParams,
+) -> Result<(), DbErr> {
     let collection = collection::ActiveModel {
         address: Set(params.address),
         name: Set(params.name),
@@ -25,65 +43,125 @@ pub async fn create(db: &DatabaseConnection, params: CreateCollectionParams) ->
         supply: Set(params.supply),
         description: Set(params.metadata.description),
         royalty: Set(params.royalty),
+        royalty_address: Set(params.royalty_address),
         banner: Set(params.metadata.banner),
         image: Set(params.metadata.pfp),
-        socials: Set(if let Some(socials) = params.metadata.socials {
-            Some(socials)
-        } else {
-            None
-        }),
-        ..Default::default()
+        socials: Set(params.metadata.socials),
     };
 
+    // every token minted in a tx of a new collection carries its params, the later ones conflict
     Collection::insert(collection)
         .on_conflict(
             OnConflict::column(collection::Column::Address)
                 .do_nothing()
                 .to_owned(),
         )
-        .exec(db)
+        .exec_without_returning(db)
         .await?;
 
     Ok(())
 }
 
+pub async fn update_royalty(
+    db: &DatabaseConnection,
+    address: &str,
+    royalty: Option<Decimal>,
+    royalty_address: Option<String>,
+) -> Result<(), DbErr> {
+    let collection = collection::ActiveModel {
+        address: Unchanged(address.to_owned()),
+        royalty: Set(royalty),
+        royalty_address: Set(royalty_address),
+        ..Default::default()
+    };
+
+    Collection::update(collection).exec(db).await?;
+
+    Ok(())
+}
+
 pub async fn find_collections_with_stats(
     db: &DatabaseConnection,
     cols: impl IntoIterator<Item = CollectionStatSelectOption>,
     search: Option<String>,
     (page, limit): (Option<u32>, Option<u16>),
     (col, sort): (CollectionStatSelectOption, Sort),
-) -> Result<Vec<CollectionWithStat>, DbErr> {
-    let cols = cols
-        .into_iter()
-        .map(|col| col.scribe())
-        .collect::<Vec<&str>>()
-        .join(",");
-
-    let limit = limit.unwrap_or(100);
-    let skip = (page.unwrap_or(1) - 1) * limit as u32;
-    let col = col.scribe();
-    let sort = sort.scribe();
-    let search = search
-        .map(|s| format!("LOWER(name) LIKE '%{}%'", s.to_lowercase()))
-        .unwrap_or("1 = 1".to_owned());
-
-    let collections = query::JsonValue::find_by_statement(Statement::from_sql_and_values(
-        DatabaseBackend::Postgres,
-        format!(
-            "SELECT {} FROM collection_view
-            WHERE {}
-            ORDER BY {} {} NULLS LAST 
-            OFFSET {} LIMIT {};",
-            cols, search, col, sort, skip, limit
-        ),
-        [],
+) -> Result<(Vec<CollectionWithStat>, u64), DbErr> {
+    let backend = db.get_database_backend();
+    let search = search.as_deref();
+
+    let collections = CollectionWithStat::find_by_statement(backend.build(
+        &select_collections_with_stats(cols, search, (page, limit), (col, sort)),
     ))
-    .into_model::<CollectionWithStat>()
     .all(db)
     .await?;
 
-    Ok(collections)
+    let total =
+        CollectionCount::find_by_statement(backend.build(&count_collections_with_stats(search)))
+            .one(db)
+            .await?
+            .map(|CollectionCount { total }| total as u64)
+            .unwrap_or_default();
+
+    Ok((collections, total))
+}
+
+pub fn select_collections_with_stats(
+    cols: impl IntoIterator<Item = CollectionStatSelectOption>,
+    search: Option<&str>,
+    (page, limit): (Option<u32>, Option<u16>),
+    (col, sort): (CollectionStatSelectOption, Sort),
+) -> SelectStatement {
+    let limit = limit.unwrap_or(100) as u64;
+    let skip = page.unwrap_or(1).saturating_sub(1) as u64 * limit;
+
+    // the sort column is usually one of the selected ones already
+    let mut names = Vec::<&str>::new();
+
+    for col in cols {
+        if !names.contains(&col.scribe()) {
+            names.push(col.scribe());
+        }
+    }
+
+    Query::select()
+        .columns(names.into_iter().map(Alias::new))
+        .from(Alias::new(COLLECTION_VIEW))
+        .cond_where(search_condition(search))
+        .order_by_with_nulls(Alias::new(col.scribe()), sort.into(), NullOrdering::Last)
+        .offset(skip)
+        .limit(limit)
+        .to_owned()
+}
+
+pub fn count_collections_with_stats(search: Option<&str>) -> SelectStatement {
+    Query::select()
+        .expr_as(Func::count(Expr::col(Asterisk)), Alias::new("total"))
+        .from(Alias::new(COLLECTION_VIEW))
+        .cond_where(search_condition(search))
+        .to_owned()
+}
+
+fn search_condition(search: Option<&str>) -> Condition {
+    let mut condition = Condition::all();
+
+    if let Some(search) = search {
+        let pattern = format!("%{}%", escape_like(&search.to_lowercase()));
+
+        condition = condition.add(
+            Expr::expr(Func::lower(Expr::col(Alias::new("name"))))
+                .like(LikeExpr::new(pattern).escape('\\')),
+        );
+    }
+
+    condition
+}
+
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
 }
 
 pub struct CreateCollectionParams {
@@ -93,6 +171,7 @@ pub struct CreateCollectionParams {
     pub supply: i32,
     pub metadata: CollectionMetadata,
     pub royalty: Option<Decimal>,
+    pub royalty_address: Option<String>,
 }
 
 #[derive(ScribeStaticStr, Clone, Copy)]
@@ -153,6 +232,12 @@ pub enum CollectionStatSelectOption {
 
     #[enumscribe(str = "volume_of_30d")]
     VolumeOf30d,
+
+    #[enumscribe(str = "floor_change_24h")]
+    FloorChange24h,
+
+    #[enumscribe(str = "volume_change_24h")]
+    VolumeChange24h,
 }
 
 #[derive(Serialize, FromQueryResult, Debug)]
@@ -213,4 +298,15 @@ pub struct CollectionWithStat {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub volume_of_30d: Option<Decimal>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub floor_change_24h: Option<Decimal>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volume_change_24h: Option<Decimal>,
+}
+
+#[derive(FromQueryResult)]
+struct CollectionCount {
+    total: i64,
 }
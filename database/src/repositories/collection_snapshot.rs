@@ -0,0 +1,56 @@
+use sea_orm::prelude::DateTimeUtc;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseBackend, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, Statement,
+};
+
+use crate::entities::collection_snapshot;
+use crate::CollectionSnapshot;
+
+static SNAPSHOT_COLLECTIONS: &str = r#"
+INSERT INTO "collection_snapshot" ("collection_address", "date", "floor", "volume_of_24h")
+SELECT "s"."collection_address", NOW(), "s"."floor_price", "s"."volume_of_24h"
+FROM "public"."collection_stats" "s";
+"#;
+
+pub async fn create_for_all_collections(db: &DatabaseConnection) -> Result<u64, DbErr> {
+    let result = db
+        .execute(Statement::from_string(
+            DatabaseBackend::Postgres,
+            SNAPSHOT_COLLECTIONS,
+        ))
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
+pub async fn find_by_collection_address(
+    db: &DatabaseConnection,
+    collection_address: &str,
+    from: Option<DateTimeUtc>,
+) -> Result<Vec<collection_snapshot::Model>, DbErr> {
+    let mut query = CollectionSnapshot::find()
+        .filter(collection_snapshot::Column::CollectionAddress.eq(collection_address));
+
+    if let Some(from) = from {
+        query = query.filter(collection_snapshot::Column::Date.gte(from));
+    }
+
+    query
+        .order_by_asc(collection_snapshot::Column::Date)
+        .all(db)
+        .await
+}
+
+pub async fn find_latest_before(
+    db: &DatabaseConnection,
+    collection_address: &str,
+    date: DateTimeUtc,
+) -> Result<Option<collection_snapshot::Model>, DbErr> {
+    CollectionSnapshot::find()
+        .filter(collection_snapshot::Column::CollectionAddress.eq(collection_address))
+        .filter(collection_snapshot::Column::Date.lte(date))
+        .order_by_desc(collection_snapshot::Column::Date)
+        .one(db)
+        .await
+}
@@ -0,0 +1,112 @@
+use sea_orm::{
+    ConnectionTrait, DatabaseBackend, DatabaseConnection, DatabaseTransaction, DbErr, Statement,
+    Value,
+};
+
+// listed counts every denom, floor and volume are in usei as amounts in other denoms can not be
+// compared with them
+static UPSERT_COLLECTION_STATS: &str = r#"
+INSERT INTO "collection_stats" (
+    "collection_address", "listed", "floor_price", "highest_bid", "sales", "volume",
+    "volume_of_1h", "volume_of_24h", "volume_of_7d", "volume_of_30d",
+    "floor_change_24h", "volume_change_24h", "updated_date"
+)
+SELECT
+    "c"."address",
+    coalesce("l"."listed", 0),
+    coalesce("l"."floor_price", 0),
+    "co"."highest_bid",
+    coalesce("t"."sales", 0),
+    coalesce("t"."volume", 0),
+    coalesce("t"."volume_of_1h", 0),
+    coalesce("t"."volume_of_24h", 0),
+    coalesce("t"."volume_of_7d", 0),
+    coalesce("t"."volume_of_30d", 0),
+    CASE WHEN "cs"."floor" > 0 AND "l"."floor_price" IS NOT NULL
+        THEN round(("l"."floor_price" - "cs"."floor") / "cs"."floor" * 100, 2)
+    END,
+    CASE WHEN "t"."volume_of_prev_24h" > 0
+        THEN round((coalesce("t"."volume_of_24h", 0) - "t"."volume_of_prev_24h") / "t"."volume_of_prev_24h" * 100, 2)
+    END,
+    NOW()
+FROM "public"."collection" "c"
+LEFT JOIN LATERAL (
+    SELECT count("l"."id") "listed", min("l"."price") FILTER (WHERE "l"."denom" = 'usei') "floor_price"
+    FROM "public"."listing_nft" "l"
+    WHERE "l"."collection_address" = "c"."address"
+    AND ("l"."expiration_time" IS NULL OR "l"."expiration_time" > EXTRACT(epoch FROM NOW()))
+) "l" ON TRUE
+LEFT JOIN LATERAL (
+    SELECT
+        count("t"."id") "sales",
+        sum("t"."volume") "volume",
+        sum("t"."volume") FILTER (WHERE "t"."date" > NOW() - INTERVAL '1 hour') "volume_of_1h",
+        sum("t"."volume") FILTER (WHERE "t"."date" > NOW() - INTERVAL '1 day') "volume_of_24h",
+        sum("t"."volume") FILTER (WHERE "t"."date" > NOW() - INTERVAL '7 days') "volume_of_7d",
+        sum("t"."volume") FILTER (WHERE "t"."date" > NOW() - INTERVAL '30 days') "volume_of_30d",
+        sum("t"."volume") FILTER (
+            WHERE "t"."date" > NOW() - INTERVAL '2 days' AND "t"."date" <= NOW() - INTERVAL '1 day'
+        ) "volume_of_prev_24h"
+    FROM "public"."transaction" "t"
+    WHERE "t"."collection_address" = "c"."address" AND "t"."denom" = 'usei'
+    AND NOT "t"."is_flagged"
+) "t" ON TRUE
+LEFT JOIN LATERAL (
+    SELECT max("co"."price") "highest_bid"
+    FROM "public"."collection_offer" "co"
+    WHERE "co"."collection_address" = "c"."address"
+    AND "co"."end_date" > NOW()
+    AND "co"."start_date" < NOW()
+) "co" ON TRUE
+LEFT JOIN LATERAL (
+    SELECT "cs"."floor"
+    FROM "public"."collection_snapshot" "cs"
+    WHERE "cs"."collection_address" = "c"."address"
+    AND "cs"."date" <= NOW() - INTERVAL '1 day'
+    ORDER BY "cs"."date" DESC
+    LIMIT 1
+) "cs" ON TRUE
+WHERE $1::varchar IS NULL OR "c"."address" = $1
+ON CONFLICT ("collection_address") DO UPDATE SET
+    "listed" = EXCLUDED."listed",
+    "floor_price" = EXCLUDED."floor_price",
+    "highest_bid" = EXCLUDED."highest_bid",
+    "sales" = EXCLUDED."sales",
+    "volume" = EXCLUDED."volume",
+    "volume_of_1h" = EXCLUDED."volume_of_1h",
+    "volume_of_24h" = EXCLUDED."volume_of_24h",
+    "volume_of_7d" = EXCLUDED."volume_of_7d",
+    "volume_of_30d" = EXCLUDED."volume_of_30d",
+    "floor_change_24h" = EXCLUDED."floor_change_24h",
+    "volume_change_24h" = EXCLUDED."volume_change_24h",
+    "updated_date" = EXCLUDED."updated_date";
+"#;
+
+// called by stream handlers in the same transaction that touched listings or sales
+pub async fn refresh(tx: &DatabaseTransaction, collection_address: &str) -> Result<(), DbErr> {
+    upsert(tx, Some(collection_address.to_owned())).await
+}
+
+// for admin tools that change past sales outside of a stream transaction
+pub async fn refresh_one(db: &DatabaseConnection, collection_address: &str) -> Result<(), DbErr> {
+    upsert(db, Some(collection_address.to_owned())).await
+}
+
+// volume windows keep sliding without any activity, so every collection is refreshed on a schedule
+pub async fn refresh_all(db: &DatabaseConnection) -> Result<(), DbErr> {
+    upsert(db, None).await
+}
+
+async fn upsert(
+    db: &impl ConnectionTrait,
+    collection_address: Option<String>,
+) -> Result<(), DbErr> {
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        UPSERT_COLLECTION_STATS,
+        [Value::String(collection_address.map(Box::new))],
+    ))
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,10 @@
+use sea_orm::{ConnectionTrait, DbErr, EntityTrait};
+
+use crate::Config;
+
+pub async fn find_value(db: &impl ConnectionTrait, key: &str) -> Result<Option<String>, DbErr> {
+    Ok(Config::find_by_id(key)
+        .one(db)
+        .await?
+        .map(|config| config.value))
+}
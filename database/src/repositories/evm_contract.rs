@@ -0,0 +1,42 @@
+use sea_orm::{sea_query::OnConflict, ConnectionTrait, DbErr, EntityTrait, Set};
+
+use crate::entities::evm_contract;
+use crate::EvmContract;
+
+pub async fn find_by_address(
+    db: &impl ConnectionTrait,
+    address: &str,
+) -> Result<Option<evm_contract::Model>, DbErr> {
+    EvmContract::find_by_id(address).one(db).await
+}
+
+// a pointer registered later replaces a contract first seen without one
+pub async fn upsert(
+    db: &impl ConnectionTrait,
+    params: UpsertEvmContractParams,
+) -> Result<evm_contract::Model, DbErr> {
+    let contract = evm_contract::ActiveModel {
+        address: Set(params.address),
+        cw721_address: Set(params.cw721_address),
+        is_cw721_pointer: Set(params.is_cw721_pointer),
+        ..Default::default()
+    };
+
+    EvmContract::insert(contract)
+        .on_conflict(
+            OnConflict::column(evm_contract::Column::Address)
+                .update_columns([
+                    evm_contract::Column::Cw721Address,
+                    evm_contract::Column::IsCw721Pointer,
+                ])
+                .to_owned(),
+        )
+        .exec_with_returning(db)
+        .await
+}
+
+pub struct UpsertEvmContractParams {
+    pub address: String,
+    pub cw721_address: Option<String>,
+    pub is_cw721_pointer: bool,
+}
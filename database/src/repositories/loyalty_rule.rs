@@ -0,0 +1,50 @@
+use sea_orm::prelude::DateTimeUtc;
+use sea_orm::sea_query::{Expr, Query};
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder,
+};
+
+use crate::entities::{loyalty_rule, loyalty_season};
+use crate::sea_orm_active_enums::{LoyaltyPointKind, Marketplace};
+use crate::{LoyaltyRule, LoyaltySeason};
+
+pub async fn find_applicable(
+    db: &impl ConnectionTrait,
+    kind: &LoyaltyPointKind,
+    marketplace: &Marketplace,
+    collection_address: &str,
+    date: DateTimeUtc,
+) -> Result<Option<loyalty_rule::Model>, DbErr> {
+    let running_seasons = Query::select()
+        .column(loyalty_season::Column::Id)
+        .from(LoyaltySeason)
+        .and_where(loyalty_season::Column::StartDate.lte(date))
+        .and_where(loyalty_season::Column::EndDate.gt(date))
+        .to_owned();
+
+    LoyaltyRule::find()
+        .filter(loyalty_rule::Column::IsActive.eq(true))
+        .filter(loyalty_rule::Column::Kind.eq(kind.to_owned()))
+        .filter(
+            Condition::any()
+                .add(loyalty_rule::Column::Marketplace.is_null())
+                .add(loyalty_rule::Column::Marketplace.eq(marketplace.to_owned())),
+        )
+        .filter(
+            Condition::any()
+                .add(loyalty_rule::Column::CollectionAddress.is_null())
+                .add(loyalty_rule::Column::CollectionAddress.eq(collection_address)),
+        )
+        .filter(
+            Condition::any()
+                .add(loyalty_rule::Column::SeasonId.is_null())
+                .add(loyalty_rule::Column::SeasonId.in_subquery(running_seasons)),
+        )
+        // every narrowed dimension makes a rule more specific, the newest rule breaks ties
+        .order_by_desc(Expr::cust(
+            r#"("marketplace" IS NOT NULL)::int + ("collection_address" IS NOT NULL)::int + ("season_id" IS NOT NULL)::int"#,
+        ))
+        .order_by_desc(loyalty_rule::Column::Id)
+        .one(db)
+        .await
+}
@@ -0,0 +1,135 @@
+use sea_orm::prelude::DateTimeUtc;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend, DbErr, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, Set, Statement, TransactionTrait,
+};
+
+use crate::entities::{loyalty_season, loyalty_season_snapshot};
+use crate::{LoyaltySeason, LoyaltySeasonSnapshot};
+
+// the guard makes freezing a one shot operation even when two admins run it at once
+static FREEZE_SEASON: &str = r#"
+UPDATE "loyalty_season" SET "snapshot_date" = NOW()
+WHERE "id" = $1 AND "snapshot_date" IS NULL
+"#;
+
+static INSERT_SEASON_SNAPSHOT: &str = r#"
+INSERT INTO "loyalty_season_snapshot" ("season_id", "wallet_address", "point", "rank")
+SELECT
+    "s"."id",
+    "p"."wallet_address",
+    sum("p"."point"),
+    RANK() OVER (ORDER BY sum("p"."point") DESC)
+FROM "loyalty_season" "s"
+JOIN "user_loyalty_point" "p" ON "p"."date" >= "s"."start_date" AND "p"."date" < "s"."end_date"
+WHERE "s"."id" = $1
+GROUP BY "s"."id", "p"."wallet_address"
+"#;
+
+pub async fn create(
+    db: &DatabaseConnection,
+    params: CreateLoyaltySeasonParams,
+) -> Result<loyalty_season::Model, DbErr> {
+    let season = loyalty_season::ActiveModel {
+        name: Set(params.name),
+        start_date: Set(params.start_date.into()),
+        end_date: Set(params.end_date.into()),
+        ..Default::default()
+    };
+
+    LoyaltySeason::insert(season).exec_with_returning(db).await
+}
+
+pub async fn find_by_id(
+    db: &DatabaseConnection,
+    id: i32,
+) -> Result<Option<loyalty_season::Model>, DbErr> {
+    LoyaltySeason::find_by_id(id).one(db).await
+}
+
+pub async fn find_running(
+    db: &DatabaseConnection,
+    date: DateTimeUtc,
+) -> Result<Option<loyalty_season::Model>, DbErr> {
+    LoyaltySeason::find()
+        .filter(loyalty_season::Column::StartDate.lte(date))
+        .filter(loyalty_season::Column::EndDate.gt(date))
+        .order_by_desc(loyalty_season::Column::StartDate)
+        .one(db)
+        .await
+}
+
+pub async fn find_all(db: &DatabaseConnection) -> Result<Vec<loyalty_season::Model>, DbErr> {
+    LoyaltySeason::find()
+        .order_by_asc(loyalty_season::Column::StartDate)
+        .all(db)
+        .await
+}
+
+// returns the number of frozen wallets, or none when the season was already frozen
+pub async fn take_snapshot(db: &DatabaseConnection, season_id: i32) -> Result<Option<u64>, DbErr> {
+    let tx = db.begin().await?;
+
+    let frozen = tx
+        .execute(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            FREEZE_SEASON,
+            [season_id.into()],
+        ))
+        .await?;
+
+    if frozen.rows_affected() == 0 {
+        tx.rollback().await?;
+
+        return Ok(None);
+    }
+
+    let inserted = tx
+        .execute(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            INSERT_SEASON_SNAPSHOT,
+            [season_id.into()],
+        ))
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(Some(inserted.rows_affected()))
+}
+
+pub async fn find_snapshot(
+    db: &DatabaseConnection,
+    season_id: i32,
+    (page, limit): (Option<u32>, Option<u16>),
+) -> Result<(Vec<loyalty_season_snapshot::Model>, u64), DbErr> {
+    let paginator = LoyaltySeasonSnapshot::find()
+        .filter(loyalty_season_snapshot::Column::SeasonId.eq(season_id))
+        .order_by_asc(loyalty_season_snapshot::Column::Rank)
+        .order_by_asc(loyalty_season_snapshot::Column::WalletAddress)
+        .paginate(db, limit.unwrap_or(100) as u64);
+
+    let total = paginator.num_items().await?;
+    let entries = paginator
+        .fetch_page(page.unwrap_or(1).saturating_sub(1) as u64)
+        .await?;
+
+    Ok((entries, total))
+}
+
+pub async fn find_full_snapshot(
+    db: &DatabaseConnection,
+    season_id: i32,
+) -> Result<Vec<loyalty_season_snapshot::Model>, DbErr> {
+    LoyaltySeasonSnapshot::find()
+        .filter(loyalty_season_snapshot::Column::SeasonId.eq(season_id))
+        .order_by_asc(loyalty_season_snapshot::Column::Rank)
+        .order_by_asc(loyalty_season_snapshot::Column::WalletAddress)
+        .all(db)
+        .await
+}
+
+pub struct CreateLoyaltySeasonParams {
+    pub name: String,
+    pub start_date: DateTimeUtc,
+    pub end_date: DateTimeUtc,
+}
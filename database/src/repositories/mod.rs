@@ -1,6 +1,17 @@
 pub mod collection;
+pub mod collection_snapshot;
+pub mod collection_stats;
+pub mod config;
+pub mod evm_contract;
+pub mod loyalty_rule;
+pub mod loyalty_season;
 pub mod nft;
 pub mod nft_activity;
+pub mod nft_trait;
+pub mod rarity;
+pub mod stream_checkpoint;
 pub mod tracing;
 pub mod transaction;
+pub mod user;
 pub mod user_point;
+pub mod wash_trade;
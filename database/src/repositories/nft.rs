@@ -1,20 +1,26 @@
 use std::str::FromStr;
 
-use chrono::DateTime;
+use chrono::{DateTime, Utc};
 use sea_orm::prelude::{DateTimeUtc, DateTimeWithTimeZone, Decimal};
-use sea_orm::DatabaseTransaction;
+use sea_orm::sea_query::{Alias, Condition, Expr, NullOrdering, Order, Query};
 use sea_orm::{
-    sea_query::OnConflict, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, Set,
-    TransactionTrait,
+    sea_query::OnConflict, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, Set,
 };
+use sea_orm::{
+    DatabaseTransaction, FromQueryResult, JoinType, PaginatorTrait, QueryOrder, QuerySelect,
+    RelationTrait, Select,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use service::{NftAttribute, PalletListing};
 
-use crate::entities::{listing_nft, nft, nft_trait};
+use crate::entities::{listing_nft, nft, nft_bidding, nft_offer, nft_trait};
 use crate::sea_orm_active_enums::{Marketplace, SaleType};
-use crate::{ListingNft, Nft, NftTrait};
+use crate::{ListingNft, Nft, NftBidding, NftOffer, NftTrait, Sort};
 
 pub async fn find_by_address_and_token_id(
-    db: &DatabaseConnection,
+    db: &impl ConnectionTrait,
     token_address: &str,
     token_id: &str,
 ) -> Result<Option<nft::Model>, DbErr> {
@@ -25,8 +31,23 @@ pub async fn find_by_address_and_token_id(
         .await
 }
 
-pub async fn find_listing_by_nft_id(
+// any token works for collection wide queries like royalties
+pub async fn find_first_token_id(
     db: &DatabaseConnection,
+    token_address: &str,
+) -> Result<Option<String>, DbErr> {
+    Nft::find()
+        .select_only()
+        .column(nft::Column::TokenId)
+        .filter(nft::Column::TokenAddress.eq(token_address))
+        .order_by_asc(nft::Column::Id)
+        .into_tuple()
+        .one(db)
+        .await
+}
+
+pub async fn find_listing_by_nft_id(
+    db: &impl ConnectionTrait,
     nft_id: i32,
 ) -> Result<Option<listing_nft::Model>, DbErr> {
     ListingNft::find()
@@ -35,10 +56,59 @@ pub async fn find_listing_by_nft_id(
         .await
 }
 
-pub async fn update_owner(
+pub async fn find_detail(
     db: &DatabaseConnection,
     token_address: &str,
     token_id: &str,
+) -> Result<Option<NftDetail>, DbErr> {
+    let Some(nft) = find_by_address_and_token_id(db, token_address, token_id).await? else {
+        return Ok(None);
+    };
+
+    let traits = NftTrait::find()
+        .filter(nft_trait::Column::NftId.eq(nft.id))
+        .order_by_asc(nft_trait::Column::Attribute)
+        .all(db)
+        .await?;
+
+    let listing = find_listing_by_nft_id(db, nft.id)
+        .await?
+        .filter(|listing| !is_expired(listing));
+
+    let biddings = match &listing {
+        Some(listing) => {
+            NftBidding::find()
+                .filter(nft_bidding::Column::ListingId.eq(listing.id))
+                .order_by_desc(nft_bidding::Column::Price)
+                .all(db)
+                .await?
+        }
+        None => vec![],
+    };
+
+    let now = Utc::now();
+
+    let offers = NftOffer::find()
+        .filter(nft_offer::Column::NftId.eq(nft.id))
+        .filter(nft_offer::Column::StartDate.lte(now))
+        .filter(nft_offer::Column::EndDate.gt(now))
+        .order_by_desc(nft_offer::Column::Price)
+        .all(db)
+        .await?;
+
+    Ok(Some(NftDetail {
+        nft,
+        traits,
+        listing,
+        biddings,
+        offers,
+    }))
+}
+
+pub async fn update_owner(
+    db: &impl ConnectionTrait,
+    token_address: &str,
+    token_id: &str,
     owner: Option<String>,
 ) -> Result<(), DbErr> {
     let nft = nft::ActiveModel {
@@ -56,9 +126,7 @@ pub async fn update_owner(
     Ok(())
 }
 
-pub async fn create(db: &DatabaseConnection, params: CreateNftParams) -> Result<i32, DbErr> {
-    let txn = db.begin().await?;
-
+pub async fn create(tx: &DatabaseTransaction, params: CreateNftParams) -> Result<i32, DbErr> {
     let nft = nft::ActiveModel {
         token_address: Set(params.token_address),
         token_id: Set(params.token_id),
@@ -76,35 +144,101 @@ pub async fn create(db: &DatabaseConnection, params: CreateNftParams) -> Result<
                 .do_nothing()
                 .to_owned(),
         )
-        .exec(db)
+        .exec(tx)
         .await?
         .last_insert_id;
 
-    let traits = params.traits.unwrap_or_default().into_iter().map(
+    insert_traits(tx, nft_id, params.traits).await?;
+
+    Ok(nft_id)
+}
+
+// metadata changed after mint, the traits are replaced as a whole
+pub async fn update_metadata(
+    tx: &DatabaseTransaction,
+    params: UpdateNftMetadataParams,
+) -> Result<Option<i32>, DbErr> {
+    let Some(nft) =
+        find_by_address_and_token_id(tx, &params.token_address, &params.token_id).await?
+    else {
+        return Ok(None);
+    };
+
+    let model = nft::ActiveModel {
+        token_uri: Set(params.token_uri),
+        description: Set(params.description),
+        name: Set(params.name),
+        image: Set(params.image),
+        ..Default::default()
+    };
+
+    Nft::update_many()
+        .set(model)
+        .filter(nft::Column::Id.eq(nft.id))
+        .exec(tx)
+        .await?;
+
+    NftTrait::delete_many()
+        .filter(nft_trait::Column::NftId.eq(nft.id))
+        .exec(tx)
+        .await?;
+
+    insert_traits(tx, nft.id, params.traits).await?;
+
+    Ok(Some(nft.id))
+}
+
+async fn insert_traits(
+    tx: &DatabaseTransaction,
+    nft_id: i32,
+    traits: Option<Vec<NftAttribute>>,
+) -> Result<(), DbErr> {
+    let traits = traits.unwrap_or_default().into_iter().map(
         |NftAttribute {
              trait_type,
              r#type,
              value,
              display_type,
-         }| nft_trait::ActiveModel {
-            nft_id: Set(nft_id),
-            attribute: Set(trait_type.unwrap_or(r#type.unwrap_or("unknown".to_string()))),
-            display_type: Set(display_type.map(|v| v.to_string())),
-            value: Set(value
-                .map(|v| v.to_string())
-                .unwrap_or("unknown".to_string())),
-            ..Default::default()
+         }| {
+            let display_type = display_type.map(trait_value_to_string);
+
+            nft_trait::ActiveModel {
+                nft_id: Set(nft_id),
+                attribute: Set(trait_type.unwrap_or(r#type.unwrap_or("unknown".to_string()))),
+                numeric_value: Set(value.as_ref().and_then(trait_numeric_value)),
+                display_type: Set(display_type),
+                value: Set(value
+                    .map(trait_value_to_string)
+                    .unwrap_or("unknown".to_string())),
+                ..Default::default()
+            }
         },
     );
 
     NftTrait::insert_many(traits)
         .on_empty_do_nothing()
-        .exec(db)
+        .exec(tx)
         .await?;
 
-    txn.commit().await?;
+    Ok(())
+}
+
+fn trait_value_to_string(value: Value) -> String {
+    match value {
+        Value::String(value) => value,
+        value => value.to_string(),
+    }
+}
 
-    Ok(nft_id)
+// numbers and dates (unix timestamps) are kept numeric so they can be filtered by range
+fn trait_numeric_value(value: &Value) -> Option<Decimal> {
+    match value {
+        Value::Number(number) => Decimal::from_str(&number.to_string())
+            .or_else(|_| Decimal::from_scientific(&number.to_string()))
+            .ok(),
+        Value::String(value) => Decimal::from_str(value.trim()).ok(),
+        _ => None,
+    }
 }
 
 pub async fn create_pallet_listing(
@@ -157,6 +291,161 @@ pub async fn delete_listing_if_exist(tx: &DatabaseTransaction, nft_id: i32) -> R
     Ok(())
 }
 
+pub async fn create_offer(
+    tx: &DatabaseTransaction,
+    params: CreateNftOfferParams,
+) -> Result<(), DbErr> {
+    let offer = nft_offer::ActiveModel {
+        tx_hash: Set(params.tx_hash),
+        created_date: Set(params.created_date.into()),
+        nft_id: Set(params.nft_id),
+        price: Set(params.price),
+        buyer_address: Set(params.buyer_address),
+        start_date: Set(params.created_date.into()),
+        end_date: Set(params.end_date.into()),
+        denom: Set(params.denom),
+        ..Default::default()
+    };
+
+    NftOffer::insert(offer).exec(tx).await?;
+
+    Ok(())
+}
+
+// a wallet holds at most one offer per nft on pallet, cancelling removes every one it made
+pub async fn delete_offers_of_buyer(
+    tx: &DatabaseTransaction,
+    nft_id: i32,
+    buyer_address: &str,
+) -> Result<Vec<nft_offer::Model>, DbErr> {
+    let offers = NftOffer::find()
+        .filter(nft_offer::Column::NftId.eq(nft_id))
+        .filter(nft_offer::Column::BuyerAddress.eq(buyer_address))
+        .all(tx)
+        .await?;
+
+    NftOffer::delete_many()
+        .filter(nft_offer::Column::NftId.eq(nft_id))
+        .filter(nft_offer::Column::BuyerAddress.eq(buyer_address))
+        .exec(tx)
+        .await?;
+
+    Ok(offers)
+}
+
+pub async fn find_listed_by_collection(
+    db: &DatabaseConnection,
+    collection_address: &str,
+    traits: &[TraitFilter],
+    (page, limit): (Option<u32>, Option<u16>),
+    (col, sort): (NftSortOption, Sort),
+) -> Result<(Vec<NftWithListing>, u64), DbErr> {
+    let query = select_nfts_with_listing(JoinType::InnerJoin)
+        .filter(nft::Column::TokenAddress.eq(collection_address))
+        .filter(traits_condition(traits))
+        .filter(
+            Condition::any()
+                .add(listing_nft::Column::ExpirationTime.is_null())
+                .add(listing_nft::Column::ExpirationTime.gt(Utc::now().timestamp() as i32)),
+        );
+
+    paginate_nfts_with_listing(db, query, (page, limit), (col, sort)).await
+}
+
+pub async fn find_by_owner(
+    db: &DatabaseConnection,
+    owner_address: &str,
+    traits: &[TraitFilter],
+    (page, limit): (Option<u32>, Option<u16>),
+    (col, sort): (NftSortOption, Sort),
+) -> Result<(Vec<NftWithListing>, u64), DbErr> {
+    let query = select_nfts_with_listing(JoinType::LeftJoin)
+        .filter(nft::Column::OwnerAddress.eq(owner_address))
+        .filter(traits_condition(traits));
+
+    paginate_nfts_with_listing(db, query, (page, limit), (col, sort)).await
+}
+
+fn select_nfts_with_listing(listing_join: JoinType) -> Select<Nft> {
+    Nft::find()
+        .select_only()
+        .columns([
+            nft::Column::Id,
+            nft::Column::TokenAddress,
+            nft::Column::TokenId,
+            nft::Column::Name,
+            nft::Column::Image,
+            nft::Column::OwnerAddress,
+            nft::Column::RarityScore,
+            nft::Column::RarityRank,
+        ])
+        .column_as(listing_nft::Column::Price, "price")
+        .column_as(listing_nft::Column::Denom, "denom")
+        .column_as(listing_nft::Column::SellerAddress, "seller_address")
+        .column_as(listing_nft::Column::CreatedDate, "listed_date")
+        .column_as(
+            Expr::col((ListingNft, listing_nft::Column::Market)).cast_as(Alias::new("text")),
+            "market",
+        )
+        .join(listing_join, nft::Relation::ListingNft.def())
+}
+
+fn traits_condition(traits: &[TraitFilter]) -> Condition {
+    traits.iter().fold(Condition::all(), |condition, filter| {
+        let matches = match filter {
+            TraitFilter::Values { attribute, values } => Condition::all()
+                .add(nft_trait::Column::Attribute.eq(attribute))
+                .add(nft_trait::Column::Value.is_in(values)),
+            TraitFilter::Range {
+                attribute,
+                min,
+                max,
+            } => Condition::all()
+                .add(nft_trait::Column::Attribute.eq(attribute))
+                .add_option(min.map(|min| nft_trait::Column::NumericValue.gte(min)))
+                .add_option(max.map(|max| nft_trait::Column::NumericValue.lte(max))),
+        };
+
+        condition.add(
+            nft::Column::Id.in_subquery(
+                Query::select()
+                    .column(nft_trait::Column::NftId)
+                    .from(NftTrait)
+                    .cond_where(matches)
+                    .to_owned(),
+            ),
+        )
+    })
+}
+
+async fn paginate_nfts_with_listing(
+    db: &DatabaseConnection,
+    mut query: Select<Nft>,
+    (page, limit): (Option<u32>, Option<u16>),
+    (col, sort): (NftSortOption, Sort),
+) -> Result<(Vec<NftWithListing>, u64), DbErr> {
+    let order = match col {
+        NftSortOption::Price => Expr::col((ListingNft, listing_nft::Column::Price)),
+        NftSortOption::ListedDate => Expr::col((ListingNft, listing_nft::Column::CreatedDate)),
+        NftSortOption::Rarity => Expr::col((Nft, nft::Column::RarityRank)),
+    };
+
+    QueryOrder::query(&mut query)
+        .order_by_expr_with_nulls(order.into(), sort.into(), NullOrdering::Last)
+        .order_by((Nft, nft::Column::Id), Order::Asc);
+
+    let paginator = query
+        .into_model::<NftWithListing>()
+        .paginate(db, limit.unwrap_or(100) as u64);
+
+    let total = paginator.num_items().await?;
+    let nfts = paginator
+        .fetch_page(page.unwrap_or(1).saturating_sub(1) as u64)
+        .await?;
+
+    Ok((nfts, total))
+}
+
 pub struct CreateNftParams {
     pub token_address: String,
     pub token_id: String,
@@ -168,6 +457,26 @@ pub struct CreateNftParams {
     pub owner_address: Option<String>,
 }
 
+pub struct UpdateNftMetadataParams {
+    pub token_address: String,
+    pub token_id: String,
+    pub token_uri: String,
+    pub name: Option<String>,
+    pub image: Option<String>,
+    pub traits: Option<Vec<NftAttribute>>,
+    pub description: Option<String>,
+}
+
+pub struct CreateNftOfferParams {
+    pub nft_id: i32,
+    pub tx_hash: String,
+    pub created_date: DateTimeUtc,
+    pub end_date: DateTimeUtc,
+    pub price: Decimal,
+    pub denom: String,
+    pub buyer_address: String,
+}
+
 pub struct CreatePalletListingParams {
     pub nft_id: i32,
     pub collection_address: String,
@@ -178,3 +487,71 @@ pub struct CreatePalletListingParams {
     pub seller: String,
     pub expiration_time: Option<i32>,
 }
+
+#[derive(Clone, Copy)]
+pub enum NftSortOption {
+    Price,
+    Rarity,
+    ListedDate,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct NftDetail {
+    pub nft: nft::Model,
+    pub traits: Vec<nft_trait::Model>,
+    pub listing: Option<listing_nft::Model>,
+    pub biddings: Vec<nft_bidding::Model>,
+    pub offers: Vec<nft_offer::Model>,
+}
+
+impl NftDetail {
+    // a cached detail can outlive its listing and offers
+    pub fn drop_expired(&mut self) {
+        if self.listing.as_ref().is_some_and(is_expired) {
+            self.listing = None;
+            self.biddings.clear();
+        }
+
+        let now = Utc::now();
+
+        self.offers.retain(|offer| offer.end_date > now);
+    }
+}
+
+fn is_expired(listing: &listing_nft::Model) -> bool {
+    listing
+        .expiration_time
+        .is_some_and(|expiration_time| expiration_time as i64 <= Utc::now().timestamp())
+}
+
+// `values` is tried first, a filter with only `min` and/or `max` is a range on numeric traits
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum TraitFilter {
+    Values {
+        attribute: String,
+        values: Vec<String>,
+    },
+    Range {
+        attribute: String,
+        min: Option<Decimal>,
+        max: Option<Decimal>,
+    },
+}
+
+#[derive(Serialize, FromQueryResult, Debug)]
+pub struct NftWithListing {
+    pub id: i32,
+    pub token_address: String,
+    pub token_id: String,
+    pub name: Option<String>,
+    pub image: Option<String>,
+    pub owner_address: Option<String>,
+    pub rarity_score: Option<Decimal>,
+    pub rarity_rank: Option<i32>,
+    pub price: Option<Decimal>,
+    pub denom: Option<String>,
+    pub seller_address: Option<String>,
+    pub listed_date: Option<DateTimeWithTimeZone>,
+    pub market: Option<Marketplace>,
+}
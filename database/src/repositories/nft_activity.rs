@@ -1,12 +1,16 @@
 use crate::{
-    entities::nft_activity,
+    entities::{nft, nft_activity},
     sea_orm_active_enums::{Marketplace, NftActivityKind},
     NftActivity,
 };
 use sea_orm::{
-    prelude::{DateTimeUtc, Decimal},
-    DatabaseTransaction, DbErr, EntityTrait, Set,
+    prelude::{DateTimeUtc, DateTimeWithTimeZone, Decimal},
+    sea_query::{Alias, Expr},
+    ColumnTrait, Condition, DatabaseConnection, DatabaseTransaction, DbErr, EntityTrait,
+    FromQueryResult, JoinType, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, QueryTrait,
+    RelationTrait, Set,
 };
+use serde::Serialize;
 
 pub async fn create(
     tx: &DatabaseTransaction,
@@ -31,6 +35,125 @@ pub async fn create(
     Ok(())
 }
 
+pub async fn find_by_nft_id(
+    db: &DatabaseConnection,
+    nft_id: i32,
+    (page, limit): (Option<u32>, Option<u16>),
+) -> Result<(Vec<nft_activity::Model>, u64), DbErr> {
+    let paginator = NftActivity::find()
+        .filter(nft_activity::Column::NftId.eq(nft_id))
+        .order_by_desc(nft_activity::Column::Date)
+        .order_by_desc(nft_activity::Column::Id)
+        .paginate(db, limit.unwrap_or(100) as u64);
+
+    let total = paginator.num_items().await?;
+    let activities = paginator
+        .fetch_page(page.unwrap_or(1).saturating_sub(1) as u64)
+        .await?;
+
+    Ok((activities, total))
+}
+
+pub async fn find_feed(
+    db: &DatabaseConnection,
+    filter: ActivityFilter,
+    cursor: Option<ActivityCursor>,
+    limit: u16,
+) -> Result<(Vec<ActivityWithNft>, Option<ActivityCursor>), DbErr> {
+    let ActivityFilter {
+        collection_address,
+        token_id,
+        wallet_address,
+        event_kind,
+        marketplace,
+        from,
+        to,
+    } = filter;
+
+    let mut query = NftActivity::find()
+        .select_only()
+        .columns([
+            nft_activity::Column::Id,
+            nft_activity::Column::TxHash,
+            nft_activity::Column::SellerAddress,
+            nft_activity::Column::BuyerAddress,
+            nft_activity::Column::Date,
+            nft_activity::Column::Price,
+            nft_activity::Column::Denom,
+        ])
+        .column_as(
+            Expr::col((NftActivity, nft_activity::Column::EventKind)).cast_as(Alias::new("text")),
+            "event_kind",
+        )
+        .column_as(
+            Expr::col((NftActivity, nft_activity::Column::Market)).cast_as(Alias::new("text")),
+            "market",
+        )
+        .column_as(nft::Column::TokenAddress, "token_address")
+        .column_as(nft::Column::TokenId, "token_id")
+        .column_as(nft::Column::Name, "name")
+        .column_as(nft::Column::Image, "image")
+        .join(JoinType::InnerJoin, nft_activity::Relation::Nft.def())
+        .apply_if(collection_address, |query, address| {
+            query.filter(nft::Column::TokenAddress.eq(address))
+        })
+        .apply_if(token_id, |query, token_id| {
+            query.filter(nft::Column::TokenId.eq(token_id))
+        })
+        .apply_if(wallet_address, |query, wallet| {
+            query.filter(
+                Condition::any()
+                    .add(nft_activity::Column::BuyerAddress.eq(wallet.to_owned()))
+                    .add(nft_activity::Column::SellerAddress.eq(wallet)),
+            )
+        })
+        .apply_if(event_kind, |query, kind| {
+            query.filter(nft_activity::Column::EventKind.eq(kind))
+        })
+        .apply_if(marketplace, |query, market| {
+            query.filter(nft_activity::Column::Market.eq(market))
+        })
+        .apply_if(from, |query, from| {
+            query.filter(nft_activity::Column::Date.gte(from))
+        })
+        .apply_if(to, |query, to| {
+            query.filter(nft_activity::Column::Date.lt(to))
+        });
+
+    if let Some(ActivityCursor { date, id }) = cursor {
+        query = query.filter(
+            Condition::any()
+                .add(nft_activity::Column::Date.lt(date))
+                .add(
+                    Condition::all()
+                        .add(nft_activity::Column::Date.eq(date))
+                        .add(nft_activity::Column::Id.lt(id)),
+                ),
+        );
+    }
+
+    // one extra row tells whether there is a next page
+    let mut activities = query
+        .order_by_desc(nft_activity::Column::Date)
+        .order_by_desc(nft_activity::Column::Id)
+        .limit(limit as u64 + 1)
+        .into_model::<ActivityWithNft>()
+        .all(db)
+        .await?;
+
+    let next_cursor = if activities.len() > limit as usize {
+        activities.truncate(limit as usize);
+        activities.last().map(|activity| ActivityCursor {
+            date: activity.date.to_utc(),
+            id: activity.id,
+        })
+    } else {
+        None
+    };
+
+    Ok((activities, next_cursor))
+}
+
 pub struct CreateNftActivityParams {
     pub denom: String,
     pub metadata: serde_json::Value,
@@ -43,3 +166,37 @@ pub struct CreateNftActivityParams {
     pub created_date: DateTimeUtc,
     pub marketplace: Marketplace,
 }
+
+#[derive(Default)]
+pub struct ActivityFilter {
+    pub collection_address: Option<String>,
+    pub token_id: Option<String>,
+    pub wallet_address: Option<String>,
+    pub event_kind: Option<NftActivityKind>,
+    pub marketplace: Option<Marketplace>,
+    pub from: Option<DateTimeUtc>,
+    pub to: Option<DateTimeUtc>,
+}
+
+// keyset position of the last returned activity, activities are ordered by (date, id) desc
+pub struct ActivityCursor {
+    pub date: DateTimeUtc,
+    pub id: i32,
+}
+
+#[derive(Serialize, FromQueryResult, Debug)]
+pub struct ActivityWithNft {
+    pub id: i32,
+    pub tx_hash: String,
+    pub seller_address: Option<String>,
+    pub buyer_address: Option<String>,
+    pub date: DateTimeWithTimeZone,
+    pub price: Decimal,
+    pub denom: String,
+    pub event_kind: NftActivityKind,
+    pub market: Marketplace,
+    pub token_address: String,
+    pub token_id: String,
+    pub name: Option<String>,
+    pub image: Option<String>,
+}
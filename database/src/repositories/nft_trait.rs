@@ -0,0 +1,48 @@
+use sea_orm::prelude::Decimal;
+use sea_orm::{DatabaseBackend, DatabaseConnection, DbErr, FromQueryResult, Statement};
+use serde::{Deserialize, Serialize};
+use service::SEI_DENOM;
+
+static FIND_TRAIT_CATALOG: &str = r#"
+SELECT
+    "t"."attribute",
+    "t"."value",
+    count(DISTINCT "n"."id") "count",
+    round(count(DISTINCT "n"."id") * 100.0 / "s"."supply", 2) "percentage",
+    count(DISTINCT "l"."id") "listed",
+    min("l"."price") FILTER (WHERE "l"."denom" = $2) "floor_price"
+FROM "public"."nft_trait" "t"
+JOIN "public"."nft" "n" ON "n"."id" = "t"."nft_id"
+LEFT JOIN "public"."listing_nft" "l"
+    ON "l"."nft_id" = "n"."id"
+    AND ("l"."expiration_time" IS NULL OR "l"."expiration_time" > EXTRACT(epoch FROM NOW()))
+CROSS JOIN (
+    SELECT count("id") "supply" FROM "public"."nft" WHERE "token_address" = $1
+) "s"
+WHERE "n"."token_address" = $1
+GROUP BY "t"."attribute", "t"."value", "s"."supply"
+ORDER BY "t"."attribute" ASC, "count" DESC;
+"#;
+
+pub async fn find_catalog_by_collection(
+    db: &DatabaseConnection,
+    collection_address: &str,
+) -> Result<Vec<TraitSummary>, DbErr> {
+    TraitSummary::find_by_statement(Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        FIND_TRAIT_CATALOG,
+        [collection_address.into(), SEI_DENOM.into()],
+    ))
+    .all(db)
+    .await
+}
+
+#[derive(Serialize, Deserialize, FromQueryResult, Debug)]
+pub struct TraitSummary {
+    pub attribute: String,
+    pub value: String,
+    pub count: i64,
+    pub percentage: Decimal,
+    pub listed: i64,
+    pub floor_price: Option<Decimal>,
+}
@@ -0,0 +1,157 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use sea_orm::prelude::Decimal;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseBackend, DatabaseConnection, DbErr, EntityTrait,
+    FromQueryResult, JoinType, QueryFilter, QuerySelect, RelationTrait, Statement,
+};
+use serde::Serialize;
+
+use crate::entities::{nft, nft_trait};
+use crate::Nft;
+
+// a token without an attribute still carries information, so it gets this value instead
+static MISSING_TRAIT: &str = "__missing__";
+static TRAIT_COUNT_ATTRIBUTE: &str = "__trait_count__";
+
+static UPDATE_RARITY: &str = r#"
+UPDATE "nft" SET
+    "rarity_score" = "v"."rarity_score",
+    "statistical_rarity" = "v"."statistical_rarity",
+    "rarity_rank" = "v"."rarity_rank"
+FROM jsonb_to_recordset($1::jsonb) AS "v"(
+    "id" int, "rarity_score" numeric, "statistical_rarity" float8, "rarity_rank" int
+)
+WHERE "nft"."id" = "v"."id";
+"#;
+
+pub async fn refresh_collection(
+    db: &impl ConnectionTrait,
+    collection_address: &str,
+) -> Result<usize, DbErr> {
+    let rows = Nft::find()
+        .select_only()
+        .column(nft::Column::Id)
+        .column(nft_trait::Column::Attribute)
+        .column(nft_trait::Column::Value)
+        .join(JoinType::LeftJoin, nft::Relation::NftTrait.def())
+        .filter(nft::Column::TokenAddress.eq(collection_address))
+        .into_model::<TokenTraitRow>()
+        .all(db)
+        .await?;
+
+    let mut tokens = BTreeMap::<i32, BTreeMap<String, String>>::new();
+
+    for TokenTraitRow {
+        id,
+        attribute,
+        value,
+    } in rows
+    {
+        let traits = tokens.entry(id).or_default();
+
+        if let Some((attribute, value)) = attribute.zip(value) {
+            traits.insert(attribute, value);
+        }
+    }
+
+    let rarities = compute_rarity(&tokens);
+    let count = rarities.len();
+
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        UPDATE_RARITY,
+        [serde_json::json!(rarities).into()],
+    ))
+    .await?;
+
+    Ok(count)
+}
+
+pub fn compute_rarity(tokens: &BTreeMap<i32, BTreeMap<String, String>>) -> Vec<NftRarity> {
+    let supply = tokens.len() as f64;
+
+    let attributes = tokens
+        .values()
+        .flat_map(|traits| traits.keys())
+        .collect::<BTreeSet<_>>();
+
+    let with_missing = |traits: &BTreeMap<String, String>| {
+        let mut values = attributes
+            .iter()
+            .map(|attribute| {
+                let value = traits
+                    .get(*attribute)
+                    .map(String::as_str)
+                    .unwrap_or(MISSING_TRAIT);
+
+                (attribute.as_str(), value.to_owned())
+            })
+            .collect::<Vec<_>>();
+
+        values.push((TRAIT_COUNT_ATTRIBUTE, traits.len().to_string()));
+        values
+    };
+
+    let mut frequencies = HashMap::<(&str, String), usize>::new();
+
+    for traits in tokens.values() {
+        for key in with_missing(traits) {
+            *frequencies.entry(key).or_default() += 1;
+        }
+    }
+
+    let mut rarities = tokens
+        .iter()
+        .map(|(id, traits)| {
+            let (information, statistical) = with_missing(traits).into_iter().fold(
+                (0_f64, 1_f64),
+                |(information, statistical), key| {
+                    let probability = frequencies[&key] as f64 / supply;
+
+                    (information - probability.log2(), statistical * probability)
+                },
+            );
+
+            NftRarity {
+                id: *id,
+                rarity_score: Decimal::from_f64_retain(information)
+                    .unwrap_or_default()
+                    .round_dp(4),
+                statistical_rarity: statistical,
+                rarity_rank: 0,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    rarities.sort_by(|a, b| b.rarity_score.cmp(&a.rarity_score).then(a.id.cmp(&b.id)));
+
+    // tokens sharing a score share a rank, the next rank skips accordingly
+    let mut previous: Option<(Decimal, i32)> = None;
+
+    for (index, rarity) in rarities.iter_mut().enumerate() {
+        rarity.rarity_rank = match previous {
+            Some((score, rank)) if score == rarity.rarity_score => rank,
+            _ => index as i32 + 1,
+        };
+
+        previous = Some((rarity.rarity_score, rarity.rarity_rank));
+    }
+
+    rarities
+}
+
+#[derive(FromQueryResult)]
+struct TokenTraitRow {
+    id: i32,
+    attribute: Option<String>,
+    value: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct NftRarity {
+    pub id: i32,
+    pub rarity_score: Decimal,
+    pub statistical_rarity: f64,
+    pub rarity_rank: i32,
+}
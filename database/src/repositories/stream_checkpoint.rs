@@ -0,0 +1,40 @@
+use sea_orm::{sea_query::OnConflict, ConnectionTrait, DbErr, EntityTrait, Set};
+
+use crate::entities::{sea_orm_active_enums::StreamContext, stream_checkpoint};
+use crate::StreamCheckpoint;
+
+pub async fn find_height(
+    db: &impl ConnectionTrait,
+    context: StreamContext,
+) -> Result<Option<u64>, DbErr> {
+    Ok(StreamCheckpoint::find_by_id(context)
+        .one(db)
+        .await?
+        .and_then(|checkpoint| u64::try_from(checkpoint.height).ok()))
+}
+
+pub async fn save(
+    db: &impl ConnectionTrait,
+    context: StreamContext,
+    height: u64,
+) -> Result<(), DbErr> {
+    let checkpoint = stream_checkpoint::ActiveModel {
+        context: Set(context),
+        height: Set(height as i64),
+        date: Set(chrono::Utc::now().into()),
+    };
+
+    StreamCheckpoint::insert(checkpoint)
+        .on_conflict(
+            OnConflict::column(stream_checkpoint::Column::Context)
+                .update_columns([
+                    stream_checkpoint::Column::Height,
+                    stream_checkpoint::Column::Date,
+                ])
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
@@ -2,7 +2,7 @@ use crate::entities::sea_orm_active_enums::StreamContext;
 use crate::entities::stream_tx;
 use crate::StreamTx;
 use sea_orm::{prelude::DateTimeWithTimeZone, DatabaseConnection, EntityTrait};
-use sea_orm::{ColumnTrait, DbErr, QueryFilter, Set};
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, PaginatorTrait, QueryFilter, Set};
 
 pub async fn find_stream_tx_by_tx_hash(
     db: &DatabaseConnection,
@@ -14,8 +14,25 @@ pub async fn find_stream_tx_by_tx_hash(
         .await
 }
 
+// the events of a tx are recorded in the same db transaction as its writes, one success means the
+// whole tx was handled
+pub async fn is_handled(
+    db: &impl ConnectionTrait,
+    context: StreamContext,
+    tx_hash: &str,
+) -> Result<bool, DbErr> {
+    let count = StreamTx::find()
+        .filter(stream_tx::Column::TxHash.eq(tx_hash))
+        .filter(stream_tx::Column::Context.eq(context))
+        .filter(stream_tx::Column::IsFailure.eq(false))
+        .count(db)
+        .await?;
+
+    Ok(count > 0)
+}
+
 pub async fn create_stream_tx(
-    db: &DatabaseConnection,
+    db: &impl ConnectionTrait,
     params: CreateStreamTxParams,
 ) -> Result<(), DbErr> {
     let stream_tx = stream_tx::ActiveModel {
@@ -1,12 +1,32 @@
 use crate::Transaction;
 use crate::{entities::transaction, sea_orm_active_enums::Marketplace};
-use sea_orm::prelude::{DateTimeUtc, Decimal};
-use sea_orm::{DatabaseConnection, DatabaseTransaction, DbErr, EntityTrait, Set};
+use sea_orm::prelude::{DateTimeUtc, DateTimeWithTimeZone, Decimal};
+use sea_orm::{
+    DatabaseConnection, DatabaseTransaction, DbBackend, DbErr, EntityTrait, FromQueryResult, Set,
+    Statement, Value,
+};
+use serde::Serialize;
+
+// royalties were paid even on flagged sales, so creator earnings keep them
+static FIND_ROYALTY_EARNINGS: &str = r#"
+SELECT
+    date_trunc('day', "date") "date",
+    count("id") "sales",
+    sum("volume") "volume",
+    sum("royalty_amount") "royalty_amount",
+    sum("marketplace_fee") "marketplace_fee"
+FROM "transaction"
+WHERE "collection_address" = $1 AND "denom" = $4
+AND ($2::timestamptz IS NULL OR "date" >= $2)
+AND ($3::timestamptz IS NULL OR "date" < $3)
+GROUP BY 1
+ORDER BY 1
+"#;
 
 pub async fn create(
     tx: &DatabaseTransaction,
     params: CreateTransactionParams,
-) -> Result<(), DbErr> {
+) -> Result<i32, DbErr> {
     let transaction = transaction::ActiveModel {
         buyer_address: Set(params.buyer_address),
         collection_address: Set(params.collection_address),
@@ -15,20 +35,56 @@ pub async fn create(
         seller_address: Set(params.seller_address),
         txn_hash: Set(params.tx_hash),
         volume: Set(params.volume),
+        denom: Set(params.denom),
+        royalty_amount: Set(params.royalty_amount),
+        marketplace_fee: Set(params.marketplace_fee),
         ..Default::default()
     };
 
-    Transaction::insert(transaction).exec(tx).await?;
+    let result = Transaction::insert(transaction).exec(tx).await?;
+
+    Ok(result.last_insert_id)
+}
 
-    Ok(())
+// daily buckets of the window, the caller sums them for the totals
+pub async fn find_royalty_earnings(
+    db: &DatabaseConnection,
+    collection_address: &str,
+    denom: &str,
+    (from, to): (Option<DateTimeUtc>, Option<DateTimeUtc>),
+) -> Result<Vec<RoyaltyEarnings>, DbErr> {
+    RoyaltyEarnings::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        FIND_ROYALTY_EARNINGS,
+        [
+            collection_address.into(),
+            Value::ChronoDateTimeUtc(from.map(Box::new)),
+            Value::ChronoDateTimeUtc(to.map(Box::new)),
+            denom.into(),
+        ],
+    ))
+    .all(db)
+    .await
 }
 
 pub struct CreateTransactionParams {
     pub tx_hash: String,
     pub volume: Decimal,
+    pub denom: String,
     pub collection_address: String,
     pub buyer_address: String,
     pub seller_address: String,
     pub created_date: DateTimeUtc,
     pub marketplace: Marketplace,
+    pub royalty_amount: Decimal,
+    pub marketplace_fee: Decimal,
+}
+
+#[derive(FromQueryResult, Serialize, Debug)]
+pub struct RoyaltyEarnings {
+    pub date: DateTimeWithTimeZone,
+    pub sales: i64,
+    pub volume: Decimal,
+    pub royalty_amount: Decimal,
+    pub marketplace_fee: Decimal,
 }
@@ -0,0 +1,109 @@
+use crate::entities::{nft, user};
+use crate::{Nft, User};
+use sea_orm::{
+    sea_query::OnConflict, ActiveValue::Unchanged, DatabaseConnection, DbErr, EntityTrait, Set,
+};
+use serde::{Deserialize, Serialize};
+
+// the row is only created on first login, later logins keep whatever `is_new_user` became
+pub async fn find_or_create(db: &DatabaseConnection, address: &str) -> Result<user::Model, DbErr> {
+    let user = user::ActiveModel {
+        address: Set(address.to_owned()),
+        is_new_user: Set(true),
+        ..Default::default()
+    };
+
+    User::insert(user)
+        .on_conflict(
+            OnConflict::column(user::Column::Address)
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+
+    User::find_by_id(address)
+        .one(db)
+        .await?
+        .ok_or(DbErr::RecordNotFound(format!("user {}", address)))
+}
+
+// the avatar is only shown while the user still owns it
+pub async fn find_with_avatar(
+    db: &DatabaseConnection,
+    address: &str,
+) -> Result<Option<(user::Model, Option<nft::Model>)>, DbErr> {
+    let user = User::find_by_id(address)
+        .find_also_related(Nft)
+        .one(db)
+        .await?;
+
+    Ok(user.map(|(user, avatar)| {
+        let avatar = avatar.filter(|nft| nft.owner_address.as_deref() == Some(address));
+
+        (user, avatar)
+    }))
+}
+
+// saving a profile completes onboarding, none when the user never logged in
+pub async fn update_profile(
+    db: &DatabaseConnection,
+    address: &str,
+    params: UpdateProfileParams,
+) -> Result<Option<user::Model>, DbErr> {
+    let user = user::ActiveModel {
+        address: Unchanged(address.to_owned()),
+        is_new_user: Set(false),
+        display_name: Set(params.display_name),
+        avatar_nft_id: Set(params.avatar_nft_id),
+        ..Default::default()
+    };
+
+    not_found_as_none(User::update(user).exec(db).await)
+}
+
+pub async fn update_notification_settings(
+    db: &DatabaseConnection,
+    address: &str,
+    settings: NotificationSettings,
+) -> Result<Option<user::Model>, DbErr> {
+    let user = user::ActiveModel {
+        address: Unchanged(address.to_owned()),
+        notify_on_sale: Set(settings.on_sale),
+        notify_on_offer: Set(settings.on_offer),
+        notify_on_bid: Set(settings.on_bid),
+        ..Default::default()
+    };
+
+    not_found_as_none(User::update(user).exec(db).await)
+}
+
+fn not_found_as_none(result: Result<user::Model, DbErr>) -> Result<Option<user::Model>, DbErr> {
+    match result {
+        Ok(user) => Ok(Some(user)),
+        Err(DbErr::RecordNotUpdated) => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+pub struct UpdateProfileParams {
+    pub display_name: Option<String>,
+    pub avatar_nft_id: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct NotificationSettings {
+    pub on_sale: bool,
+    pub on_offer: bool,
+    pub on_bid: bool,
+}
+
+impl From<&user::Model> for NotificationSettings {
+    fn from(user: &user::Model) -> Self {
+        Self {
+            on_sale: user.notify_on_sale,
+            on_offer: user.notify_on_offer,
+            on_bid: user.notify_on_bid,
+        }
+    }
+}
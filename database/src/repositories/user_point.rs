@@ -1,7 +1,47 @@
+use crate::repositories::loyalty_rule;
 use crate::UserLoyaltyPoint;
-use crate::{entities::user_loyalty_point, sea_orm_active_enums::LoyaltyPointKind};
-use sea_orm::{prelude::DateTimeUtc, DbErr};
-use sea_orm::{DatabaseTransaction, EntityTrait, Set};
+use crate::{
+    entities::{loyalty_rule as rule, user_loyalty_point},
+    sea_orm_active_enums::{LoyaltyPointKind, Marketplace},
+};
+use chrono::Duration;
+use sea_orm::{
+    prelude::{DateTimeUtc, Decimal},
+    DbErr, FromQueryResult, PaginatorTrait, QueryOrder, Statement,
+};
+use service::SEI_DENOM;
+
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, DatabaseTransaction, EntityTrait,
+    QueryFilter, QuerySelect, Set,
+};
+use serde::Serialize;
+
+// prices are stored in the smallest unit of the denom, rules are written per whole token
+static DENOM_UNIT: u32 = 1_000_000;
+
+// listing is free, xp is earned once per window so relisting can not be farmed
+static XP_COOLDOWN: Duration = Duration::hours(24);
+
+static FIND_LEADERBOARD: &str = r#"
+SELECT "wallet_address", "point", "rank" FROM (
+    SELECT
+        "wallet_address",
+        sum("point") "point",
+        RANK() OVER (ORDER BY sum("point") DESC) "rank"
+    FROM "user_loyalty_point"
+    WHERE "date" >= $1 AND "date" < $2
+    GROUP BY "wallet_address"
+) "l"
+ORDER BY "rank", "wallet_address"
+LIMIT $3 OFFSET $4
+"#;
+
+static COUNT_LEADERBOARD: &str = r#"
+SELECT count(DISTINCT "wallet_address") "total"
+FROM "user_loyalty_point"
+WHERE "date" >= $1 AND "date" < $2
+"#;
 
 pub async fn create(tx: &DatabaseTransaction, params: CreateUserPointParams) -> Result<(), DbErr> {
     let user_point = user_loyalty_point::ActiveModel {
@@ -9,6 +49,10 @@ pub async fn create(tx: &DatabaseTransaction, params: CreateUserPointParams) ->
         kind: Set(params.kind),
         point: Set(params.point),
         wallet_address: Set(params.wallet_address),
+        tx_hash: Set(params.tx_hash),
+        collection_address: Set(params.collection_address),
+        marketplace: Set(params.marketplace),
+        rule_id: Set(params.rule_id),
         ..Default::default()
     };
 
@@ -17,9 +61,258 @@ pub async fn create(tx: &DatabaseTransaction, params: CreateUserPointParams) ->
     Ok(())
 }
 
+// the baseline rates apply when no rule is configured, nothing is recorded when the rate yields
+// no point
+pub async fn award(
+    tx: &DatabaseTransaction,
+    params: AwardUserPointParams,
+) -> Result<Option<i32>, DbErr> {
+    if params.kind == LoyaltyPointKind::Xp
+        && earned_since(
+            tx,
+            &params.wallet_address,
+            &params.kind,
+            params.date - XP_COOLDOWN,
+        )
+        .await?
+    {
+        return Ok(None);
+    }
+
+    let rule = loyalty_rule::find_applicable(
+        tx,
+        &params.kind,
+        &params.marketplace,
+        &params.collection_address,
+        params.date,
+    )
+    .await?;
+
+    let rate = rule
+        .as_ref()
+        .map(PointRate::from)
+        .unwrap_or_else(|| PointRate::baseline(&params.kind));
+
+    // rates are per sei, an amount in another denom has no sei value here so only the base
+    // point counts
+    let price = if params.denom == SEI_DENOM {
+        params.price
+    } else {
+        Decimal::ZERO
+    };
+
+    let point = compute_point(&rate, price);
+
+    if point <= 0 {
+        return Ok(None);
+    }
+
+    create(
+        tx,
+        CreateUserPointParams {
+            date: params.date,
+            kind: params.kind,
+            wallet_address: params.wallet_address,
+            point,
+            tx_hash: Some(params.tx_hash),
+            collection_address: Some(params.collection_address),
+            marketplace: Some(params.marketplace),
+            rule_id: rule.map(|rule| rule.id),
+        },
+    )
+    .await?;
+
+    Ok(Some(point))
+}
+
+// points of a withdrawn action, e.g. the bid points of a cancelled offer
+pub async fn revoke(
+    tx: &DatabaseTransaction,
+    kind: LoyaltyPointKind,
+    wallet_address: &str,
+    tx_hash: &str,
+) -> Result<u64, DbErr> {
+    let result = UserLoyaltyPoint::delete_many()
+        .filter(user_loyalty_point::Column::Kind.eq(kind))
+        .filter(user_loyalty_point::Column::WalletAddress.eq(wallet_address))
+        .filter(user_loyalty_point::Column::TxHash.eq(tx_hash))
+        .exec(tx)
+        .await?;
+
+    Ok(result.rows_affected)
+}
+
+async fn earned_since(
+    tx: &DatabaseTransaction,
+    wallet_address: &str,
+    kind: &LoyaltyPointKind,
+    since: DateTimeUtc,
+) -> Result<bool, DbErr> {
+    let count = UserLoyaltyPoint::find()
+        .filter(user_loyalty_point::Column::WalletAddress.eq(wallet_address))
+        .filter(user_loyalty_point::Column::Kind.eq(kind.to_owned()))
+        .filter(user_loyalty_point::Column::Date.gt(since))
+        .count(tx)
+        .await?;
+
+    Ok(count > 0)
+}
+
+pub async fn find_totals_by_wallet(
+    db: &DatabaseConnection,
+    wallet_address: &str,
+) -> Result<LoyaltyPointTotals, DbErr> {
+    let rows = UserLoyaltyPoint::find()
+        .select_only()
+        .column(user_loyalty_point::Column::Kind)
+        .column_as(user_loyalty_point::Column::Point.sum(), "point")
+        .filter(user_loyalty_point::Column::WalletAddress.eq(wallet_address))
+        .group_by(user_loyalty_point::Column::Kind)
+        .into_tuple::<(LoyaltyPointKind, i64)>()
+        .all(db)
+        .await?;
+
+    Ok(rows.into_iter().fold(
+        LoyaltyPointTotals::default(),
+        |mut totals, (kind, point)| {
+            match kind {
+                LoyaltyPointKind::Sell => totals.sell = point,
+                LoyaltyPointKind::Buy => totals.buy = point,
+                LoyaltyPointKind::Bid => totals.bid = point,
+                LoyaltyPointKind::Xp => totals.xp = point,
+            }
+
+            totals.total += point;
+            totals
+        },
+    ))
+}
+
+pub async fn find_history_by_wallet(
+    db: &DatabaseConnection,
+    wallet_address: &str,
+    (page, limit): (Option<u32>, Option<u16>),
+) -> Result<(Vec<user_loyalty_point::Model>, u64), DbErr> {
+    let paginator = UserLoyaltyPoint::find()
+        .filter(user_loyalty_point::Column::WalletAddress.eq(wallet_address))
+        .order_by_desc(user_loyalty_point::Column::Date)
+        .order_by_desc(user_loyalty_point::Column::Id)
+        .paginate(db, limit.unwrap_or(100) as u64);
+
+    let total = paginator.num_items().await?;
+    let points = paginator
+        .fetch_page(page.unwrap_or(1).saturating_sub(1) as u64)
+        .await?;
+
+    Ok((points, total))
+}
+
+pub async fn find_leaderboard(
+    db: &DatabaseConnection,
+    (from, to): (DateTimeUtc, DateTimeUtc),
+    (page, limit): (Option<u32>, Option<u16>),
+) -> Result<(Vec<LeaderboardEntry>, u64), DbErr> {
+    let limit = limit.unwrap_or(100) as u64;
+    let offset = page.unwrap_or(1).saturating_sub(1) as u64 * limit;
+
+    let entries = LeaderboardEntry::find_by_statement(Statement::from_sql_and_values(
+        db.get_database_backend(),
+        FIND_LEADERBOARD,
+        [from.into(), to.into(), limit.into(), offset.into()],
+    ))
+    .all(db)
+    .await?;
+
+    let total = db
+        .query_one(Statement::from_sql_and_values(
+            db.get_database_backend(),
+            COUNT_LEADERBOARD,
+            [from.into(), to.into()],
+        ))
+        .await?
+        .map(|row| row.try_get::<i64>("", "total"))
+        .transpose()?
+        .unwrap_or_default();
+
+    Ok((entries, total as u64))
+}
+
+pub fn compute_point(rate: &PointRate, price: Decimal) -> i32 {
+    let point = (rate.base_point + rate.point_per_unit * price / Decimal::from(DENOM_UNIT))
+        * rate.multiplier;
+
+    // a whale sale must not fail the whole event, the ledger column is an int
+    i32::try_from(point.floor()).unwrap_or(i32::MAX)
+}
+
+pub struct PointRate {
+    pub base_point: Decimal,
+    pub point_per_unit: Decimal,
+    pub multiplier: Decimal,
+}
+
+impl PointRate {
+    // same as the default rules of migrations/seed_loyalty_rules.sql, sales keep the historical
+    // 1 point per whole token for both sides
+    pub fn baseline(kind: &LoyaltyPointKind) -> Self {
+        let (base_point, point_per_unit) = match kind {
+            LoyaltyPointKind::Buy | LoyaltyPointKind::Sell => (Decimal::ZERO, Decimal::ONE),
+            LoyaltyPointKind::Bid => (Decimal::ZERO, Decimal::new(1, 1)),
+            LoyaltyPointKind::Xp => (Decimal::ONE, Decimal::ZERO),
+        };
+
+        Self {
+            base_point,
+            point_per_unit,
+            multiplier: Decimal::ONE,
+        }
+    }
+}
+
+impl From<&rule::Model> for PointRate {
+    fn from(rule: &rule::Model) -> Self {
+        Self {
+            base_point: rule.base_point,
+            point_per_unit: rule.point_per_unit,
+            multiplier: rule.multiplier,
+        }
+    }
+}
+
 pub struct CreateUserPointParams {
     pub date: DateTimeUtc,
     pub kind: LoyaltyPointKind,
     pub wallet_address: String,
     pub point: i32,
+    pub tx_hash: Option<String>,
+    pub collection_address: Option<String>,
+    pub marketplace: Option<Marketplace>,
+    pub rule_id: Option<i32>,
+}
+
+pub struct AwardUserPointParams {
+    pub date: DateTimeUtc,
+    pub kind: LoyaltyPointKind,
+    pub wallet_address: String,
+    pub price: Decimal,
+    pub denom: String,
+    pub tx_hash: String,
+    pub collection_address: String,
+    pub marketplace: Marketplace,
+}
+
+#[derive(Serialize, Default, Debug)]
+pub struct LoyaltyPointTotals {
+    pub total: i64,
+    pub sell: i64,
+    pub buy: i64,
+    pub bid: i64,
+    pub xp: i64,
+}
+
+#[derive(FromQueryResult, Serialize, Debug)]
+pub struct LeaderboardEntry {
+    pub wallet_address: String,
+    pub point: i64,
+    pub rank: i64,
 }
@@ -0,0 +1,167 @@
+use sea_orm::prelude::{DateTimeUtc, DateTimeWithTimeZone, Decimal};
+use sea_orm::{
+    ConnectionTrait, DatabaseConnection, DbBackend, DbErr, FromQueryResult, Statement, Value,
+};
+use serde::Serialize;
+
+// a circular trade flags both legs, the earlier sale may already be counted. a sale is compared
+// with the floor snapshotted nearest to it, not today's floor, so a re-check of old sales holds
+static FLAG_SUSPICIOUS_TRANSACTIONS: &str = r#"
+WITH "candidate" AS (
+    SELECT * FROM "transaction" WHERE $1::int IS NULL OR "id" = $1
+), "flag" AS (
+    SELECT "t"."id", 'self_trade' "reason", NULL "detail"
+    FROM "candidate" "t"
+    WHERE "t"."buyer_address" = "t"."seller_address"
+    UNION ALL
+    SELECT "leg"."id", 'circular_trade', 'reverse of transaction ' || "leg"."reverse_id"
+    FROM "candidate" "t"
+    JOIN LATERAL (
+        SELECT "r"."id"
+        FROM "transaction" "r"
+        WHERE "r"."collection_address" = "t"."collection_address"
+        AND "r"."buyer_address" = "t"."seller_address"
+        AND "r"."seller_address" = "t"."buyer_address"
+        AND "r"."date" BETWEEN "t"."date" - make_interval(hours => $2) AND "t"."date" + make_interval(hours => $2)
+        ORDER BY abs(extract(epoch FROM "r"."date" - "t"."date"))
+        LIMIT 1
+    ) "r" ON TRUE
+    CROSS JOIN LATERAL (VALUES ("t"."id", "r"."id"), ("r"."id", "t"."id")) AS "leg"("id", "reverse_id")
+    WHERE "t"."buyer_address" <> "t"."seller_address"
+    UNION ALL
+    SELECT "t"."id", 'off_floor_price', 'floor ' || "s"."floor"
+    FROM "candidate" "t"
+    JOIN LATERAL (
+        SELECT "s"."floor"
+        FROM (
+            (
+                SELECT "cs"."floor", "cs"."date"
+                FROM "collection_snapshot" "cs"
+                WHERE "cs"."collection_address" = "t"."collection_address" AND "cs"."date" <= "t"."date"
+                ORDER BY "cs"."date" DESC
+                LIMIT 1
+            )
+            UNION ALL
+            (
+                SELECT "cs"."floor", "cs"."date"
+                FROM "collection_snapshot" "cs"
+                WHERE "cs"."collection_address" = "t"."collection_address" AND "cs"."date" > "t"."date"
+                ORDER BY "cs"."date"
+                LIMIT 1
+            )
+        ) "s"
+        ORDER BY abs(extract(epoch FROM "s"."date" - "t"."date"))
+        LIMIT 1
+    ) "s" ON TRUE
+    WHERE "s"."floor" > 0 AND "t"."denom" = 'usei'
+    AND ("t"."volume" > "s"."floor" * $3 OR "t"."volume" < "s"."floor" / $3)
+)
+INSERT INTO "transaction_flag" ("transaction_id", "reason", "detail")
+SELECT DISTINCT ON ("id", "reason") "id", "reason"::"wash_trade_reason", "detail"
+FROM "flag"
+ON CONFLICT ("transaction_id", "reason") DO NOTHING
+"#;
+
+// points are revoked only for the wallets of the flagged sale, a tx can batch several sales
+static MARK_FLAGGED_TRANSACTIONS: &str = r#"
+WITH "flagged" AS (
+    UPDATE "transaction" "t" SET "is_flagged" = TRUE
+    FROM "transaction_flag" "f"
+    WHERE "f"."transaction_id" = "t"."id" AND NOT "t"."is_flagged"
+    RETURNING "t"."txn_hash", "t"."collection_address", "t"."buyer_address", "t"."seller_address"
+), "revoked" AS (
+    DELETE FROM "user_loyalty_point" "p"
+    USING "flagged" "f"
+    WHERE "p"."tx_hash" = "f"."txn_hash"
+    AND "p"."collection_address" = "f"."collection_address"
+    AND "p"."wallet_address" IN ("f"."buyer_address", "f"."seller_address")
+    AND "p"."kind" IN ('buy', 'sell')
+)
+SELECT DISTINCT "collection_address" FROM "flagged"
+"#;
+
+static FIND_FLAGGED_TRANSACTIONS: &str = r#"
+SELECT
+    "t"."id", "t"."txn_hash", "t"."date", "t"."volume", "t"."collection_address",
+    "t"."buyer_address", "t"."seller_address", "t"."market"::text "market",
+    string_agg("f"."reason"::text, ',' ORDER BY "f"."reason") "reasons",
+    string_agg("f"."detail", '; ' ORDER BY "f"."reason") "details"
+FROM "transaction" "t"
+JOIN "transaction_flag" "f" ON "f"."transaction_id" = "t"."id"
+WHERE "t"."is_flagged" AND ($1::timestamptz IS NULL OR "t"."date" >= $1)
+GROUP BY "t"."id"
+ORDER BY "t"."date" DESC
+"#;
+
+// checks a single transaction, or every transaction when none is given,
+// and returns the collections whose volume must be refreshed
+pub async fn flag_suspicious(
+    db: &impl ConnectionTrait,
+    transaction_id: Option<i32>,
+    rules: &WashTradeRules,
+) -> Result<Vec<String>, DbErr> {
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        FLAG_SUSPICIOUS_TRANSACTIONS,
+        [
+            Value::Int(transaction_id),
+            rules.circular_window_hours.into(),
+            rules.floor_ratio.into(),
+        ],
+    ))
+    .await?;
+
+    let rows = db
+        .query_all(Statement::from_string(
+            DbBackend::Postgres,
+            MARK_FLAGGED_TRANSACTIONS,
+        ))
+        .await?;
+
+    rows.iter()
+        .map(|row| row.try_get::<String>("", "collection_address"))
+        .collect()
+}
+
+pub async fn find_flagged(
+    db: &DatabaseConnection,
+    since: Option<DateTimeUtc>,
+) -> Result<Vec<FlaggedTransaction>, DbErr> {
+    FlaggedTransaction::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        FIND_FLAGGED_TRANSACTIONS,
+        [Value::ChronoDateTimeUtc(since.map(Box::new))],
+    ))
+    .all(db)
+    .await
+}
+
+pub struct WashTradeRules {
+    // a sale reversed by the same pair of wallets within this window is circular
+    pub circular_window_hours: i32,
+    // a sale priced this many times above or below floor is off floor
+    pub floor_ratio: Decimal,
+}
+
+impl Default for WashTradeRules {
+    fn default() -> Self {
+        Self {
+            circular_window_hours: 24,
+            floor_ratio: Decimal::TEN,
+        }
+    }
+}
+
+#[derive(FromQueryResult, Serialize, Debug)]
+pub struct FlaggedTransaction {
+    pub id: i32,
+    pub txn_hash: String,
+    pub date: DateTimeWithTimeZone,
+    pub volume: Decimal,
+    pub collection_address: String,
+    pub buyer_address: String,
+    pub seller_address: String,
+    pub market: String,
+    pub reasons: String,
+    pub details: Option<String>,
+}
@@ -0,0 +1,152 @@
+use database::repositories::collection::{
+    self, count_collections_with_stats, select_collections_with_stats, CollectionStatSelectOption,
+    CreateCollectionParams,
+};
+use database::repositories::nft::{self, CreateNftParams};
+use database::{Database, DatabaseBackend, Sort, TransactionTrait, Value};
+use service::CollectionMetadata;
+
+static INJECTION: &str = "'; DROP TABLE collection; --";
+static MULTI_MINT_COLLECTION: &str = "sei1multimintcollection";
+
+#[test]
+fn search_is_bound_as_parameter() {
+    let statement = DatabaseBackend::Postgres.build(&select_collections_with_stats(
+        [
+            CollectionStatSelectOption::Address,
+            CollectionStatSelectOption::Name,
+        ],
+        Some(INJECTION),
+        (Some(2), Some(10)),
+        (CollectionStatSelectOption::Volume, Sort::Desc),
+    ));
+
+    assert!(!statement.sql.contains("DROP TABLE"));
+    assert_eq!(
+        statement.sql,
+        r#"SELECT "address", "name" FROM "collection_view" WHERE LOWER("name") LIKE $1 ESCAPE E'\\' ORDER BY "volume" DESC NULLS LAST LIMIT $2 OFFSET $3"#
+    );
+
+    let values = statement.values.expect("statement must carry values").0;
+
+    assert_eq!(
+        values,
+        vec![
+            Value::String(Some(Box::new(format!("%{}%", INJECTION.to_lowercase())))),
+            Value::BigUnsigned(Some(10)),
+            Value::BigUnsigned(Some(10)),
+        ]
+    );
+}
+
+#[test]
+fn count_is_bound_as_parameter() {
+    let statement = DatabaseBackend::Postgres.build(&count_collections_with_stats(Some(INJECTION)));
+
+    assert!(!statement.sql.contains("DROP TABLE"));
+    assert_eq!(
+        statement.sql,
+        r#"SELECT COUNT(*) AS "total" FROM "collection_view" WHERE LOWER("name") LIKE $1 ESCAPE E'\\'"#
+    );
+}
+
+#[test]
+fn search_escapes_like_wildcards() {
+    let statement = DatabaseBackend::Postgres.build(&count_collections_with_stats(Some("100%_")));
+
+    let values = statement.values.expect("statement must carry values").0;
+
+    assert_eq!(
+        values,
+        vec![Value::String(Some(Box::new(r"%100\%\_%".to_owned())))]
+    );
+}
+
+#[test]
+fn missing_search_has_no_filter() {
+    let statement = DatabaseBackend::Postgres.build(&count_collections_with_stats(None));
+
+    assert_eq!(
+        statement.sql,
+        r#"SELECT COUNT(*) AS "total" FROM "collection_view" WHERE TRUE"#
+    );
+}
+
+#[test]
+fn sort_column_is_selected_once() {
+    let statement = DatabaseBackend::Postgres.build(&select_collections_with_stats(
+        [
+            CollectionStatSelectOption::Address,
+            CollectionStatSelectOption::Volume,
+            CollectionStatSelectOption::Volume,
+        ],
+        None,
+        (None, None),
+        (CollectionStatSelectOption::Volume, Sort::Desc),
+    ));
+
+    assert_eq!(
+        statement.sql,
+        r#"SELECT "address", "volume" FROM "collection_view" WHERE TRUE ORDER BY "volume" DESC NULLS LAST LIMIT $1 OFFSET $2"#
+    );
+}
+
+// the tx of a mint of several tokens carries the params of a new collection for each of them, they
+// run against the database of TEST_DATABASE_URL and are skipped without one
+#[tokio::test]
+async fn creates_collection_of_a_multi_mint_once() {
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        return;
+    };
+    let tx = Database::connect(url).await.unwrap().begin().await.unwrap();
+
+    for token_id in ["1", "2"] {
+        collection::create(&tx, new_collection()).await.unwrap();
+        nft::create(&tx, new_nft(token_id)).await.unwrap();
+    }
+
+    assert!(collection::find_by_address(&tx, MULTI_MINT_COLLECTION)
+        .await
+        .unwrap()
+        .is_some());
+
+    for token_id in ["1", "2"] {
+        assert!(
+            nft::find_by_address_and_token_id(&tx, MULTI_MINT_COLLECTION, token_id)
+                .await
+                .unwrap()
+                .is_some()
+        );
+    }
+}
+
+fn new_collection() -> CreateCollectionParams {
+    CreateCollectionParams {
+        address: MULTI_MINT_COLLECTION.to_owned(),
+        name: "Multi Mint".to_owned(),
+        symbol: "MM".to_owned(),
+        supply: 2,
+        metadata: CollectionMetadata {
+            pfp: None,
+            slug: None,
+            description: None,
+            banner: None,
+            socials: None,
+        },
+        royalty: None,
+        royalty_address: None,
+    }
+}
+
+fn new_nft(token_id: &str) -> CreateNftParams {
+    CreateNftParams {
+        token_address: MULTI_MINT_COLLECTION.to_owned(),
+        token_id: token_id.to_owned(),
+        token_uri: format!("ipfs://multi-mint/{}.json", token_id),
+        name: None,
+        image: None,
+        traits: None,
+        description: None,
+        owner_address: None,
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use database::prelude::Decimal;
+use database::repositories::rarity::{compute_rarity, NftRarity};
+
+fn tokens(traits: &[&[(&str, &str)]]) -> BTreeMap<i32, BTreeMap<String, String>> {
+    traits
+        .iter()
+        .enumerate()
+        .map(|(index, traits)| {
+            let traits = traits
+                .iter()
+                .map(|(attribute, value)| (attribute.to_string(), value.to_string()))
+                .collect();
+
+            (index as i32 + 1, traits)
+        })
+        .collect()
+}
+
+fn by_id(rarities: &[NftRarity], id: i32) -> &NftRarity {
+    rarities
+        .iter()
+        .find(|rarity| rarity.id == id)
+        .expect("every token must be ranked")
+}
+
+#[test]
+fn scores_by_information_content_and_ranks_ties_together() {
+    let rarities = compute_rarity(&tokens(&[
+        &[("background", "blue")],
+        &[("background", "blue")],
+        &[("background", "gold")],
+        &[("background", "blue")],
+    ]));
+
+    // gold is held by 1 of 4 tokens, -log2(1/4), every token shares the trait count
+    let gold = by_id(&rarities, 3);
+    assert_eq!(gold.rarity_score, Decimal::from(2));
+    assert_eq!(gold.statistical_rarity, 0.25);
+    assert_eq!(gold.rarity_rank, 1);
+
+    // -log2(3/4)
+    for id in [1, 2, 4] {
+        let blue = by_id(&rarities, id);
+        assert_eq!(blue.rarity_score, Decimal::from_str("0.4150").unwrap());
+        assert_eq!(blue.statistical_rarity, 0.75);
+        assert_eq!(blue.rarity_rank, 2);
+    }
+
+    assert_eq!(
+        rarities.iter().map(|rarity| rarity.id).collect::<Vec<_>>(),
+        vec![3, 1, 2, 4]
+    );
+}
+
+#[test]
+fn missing_attribute_counts_as_a_value() {
+    let rarities = compute_rarity(&tokens(&[&[("hat", "crown")], &[], &[], &[]]));
+
+    // the crown and the single trait are each held by 1 of 4 tokens
+    let crowned = by_id(&rarities, 1);
+    assert_eq!(crowned.rarity_score, Decimal::from(4));
+    assert_eq!(crowned.rarity_rank, 1);
+
+    // the missing hat and the empty trait count are each held by 3 of 4 tokens, -2 * log2(3/4)
+    for id in [2, 3, 4] {
+        let bare = by_id(&rarities, id);
+        assert_eq!(bare.rarity_score, Decimal::from_str("0.8301").unwrap());
+        assert_eq!(bare.statistical_rarity, 0.75 * 0.75);
+        assert_eq!(bare.rarity_rank, 2);
+    }
+}
+
+#[test]
+fn trait_count_is_scored_as_an_attribute() {
+    let rarities = compute_rarity(&tokens(&[
+        &[("eyes", "laser")],
+        &[("eyes", "laser"), ("mouth", "grin")],
+    ]));
+
+    // eyes are shared, mouth (grin or missing) and the trait count (1 or 2) each split the tokens
+    for id in [1, 2] {
+        let rarity = by_id(&rarities, id);
+        assert_eq!(rarity.rarity_score, Decimal::from(2));
+        assert_eq!(rarity.statistical_rarity, 0.25);
+        assert_eq!(rarity.rarity_rank, 1);
+    }
+}
+
+#[test]
+fn empty_collection_has_no_rarity() {
+    assert!(compute_rarity(&BTreeMap::new()).is_empty());
+}
@@ -0,0 +1,45 @@
+use database::prelude::Decimal;
+use database::repositories::user_point::{compute_point, PointRate};
+use database::sea_orm_active_enums::LoyaltyPointKind;
+
+fn usei(sei: i64) -> Decimal {
+    Decimal::from(sei * 1_000_000)
+}
+
+#[test]
+fn baseline_awards_a_point_per_whole_token_on_sales() {
+    for kind in [LoyaltyPointKind::Buy, LoyaltyPointKind::Sell] {
+        assert_eq!(compute_point(&PointRate::baseline(&kind), usei(25)), 25);
+    }
+}
+
+#[test]
+fn baseline_awards_bids_and_xp() {
+    assert_eq!(
+        compute_point(&PointRate::baseline(&LoyaltyPointKind::Bid), usei(25)),
+        2
+    );
+    assert_eq!(
+        compute_point(&PointRate::baseline(&LoyaltyPointKind::Xp), usei(25)),
+        1
+    );
+}
+
+#[test]
+fn rate_is_multiplied_and_floored() {
+    let rate = PointRate {
+        base_point: Decimal::from(3),
+        point_per_unit: Decimal::new(5, 1),
+        multiplier: Decimal::new(15, 1),
+    };
+
+    // (3 + 0.5 * 7) * 1.5 = 9.75
+    assert_eq!(compute_point(&rate, usei(7)), 9);
+}
+
+#[test]
+fn oversized_point_is_capped() {
+    let rate = PointRate::baseline(&LoyaltyPointKind::Buy);
+
+    assert_eq!(compute_point(&rate, Decimal::MAX), i32::MAX);
+}
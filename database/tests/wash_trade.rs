@@ -0,0 +1,154 @@
+use chrono::{Duration, TimeZone, Utc};
+use database::prelude::{DateTimeUtc, Decimal};
+use database::repositories::transaction::{self, CreateTransactionParams};
+use database::repositories::wash_trade::{flag_suspicious, WashTradeRules};
+use database::sea_orm_active_enums::Marketplace;
+use database::{
+    ConnectionTrait, Database, DatabaseBackend, DatabaseTransaction, Statement, TransactionTrait,
+};
+
+static COLLECTION: &str = "sei1washtradecollection";
+static ALICE: &str = "sei1alice";
+static BOB: &str = "sei1bob";
+static CAROL: &str = "sei1carol";
+
+// the rules are plain sql, they run against the database of TEST_DATABASE_URL and are skipped
+// without one. every test works in a transaction that is rolled back
+async fn begin() -> Option<DatabaseTransaction> {
+    let url = std::env::var("TEST_DATABASE_URL").ok()?;
+    let db = Database::connect(url).await.unwrap();
+
+    Some(db.begin().await.unwrap())
+}
+
+fn date(hour: i64) -> DateTimeUtc {
+    Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap() + Duration::hours(hour)
+}
+
+fn usei(sei: i64) -> Decimal {
+    Decimal::from(sei * 1_000_000)
+}
+
+async fn sale(
+    tx: &DatabaseTransaction,
+    buyer: &str,
+    seller: &str,
+    hour: i64,
+    volume: Decimal,
+) -> i32 {
+    transaction::create(
+        tx,
+        CreateTransactionParams {
+            tx_hash: format!("{}-{}-{}", buyer, seller, hour),
+            volume,
+            denom: "usei".to_owned(),
+            collection_address: COLLECTION.to_owned(),
+            buyer_address: buyer.to_owned(),
+            seller_address: seller.to_owned(),
+            created_date: date(hour),
+            marketplace: Marketplace::Pallet,
+            royalty_amount: Decimal::ZERO,
+            marketplace_fee: Decimal::ZERO,
+        },
+    )
+    .await
+    .unwrap()
+}
+
+async fn snapshot(tx: &DatabaseTransaction, hour: i64, floor: Decimal) {
+    tx.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        r#"INSERT INTO "collection_snapshot" ("collection_address", "date", "floor", "volume_of_24h") VALUES ($1, $2, $3, 0)"#,
+        [COLLECTION.into(), date(hour).into(), floor.into()],
+    ))
+    .await
+    .unwrap();
+}
+
+async fn reasons(tx: &DatabaseTransaction, transaction_id: i32) -> Vec<String> {
+    let rows = tx
+        .query_all(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r#"SELECT "reason"::text "reason" FROM "transaction_flag" WHERE "transaction_id" = $1 ORDER BY "reason""#,
+            [transaction_id.into()],
+        ))
+        .await
+        .unwrap();
+
+    rows.iter()
+        .map(|row| row.try_get::<String>("", "reason").unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn flags_self_trade() {
+    let Some(tx) = begin().await else { return };
+
+    let id = sale(&tx, ALICE, ALICE, 0, usei(10)).await;
+    let collections = flag_suspicious(&tx, Some(id), &WashTradeRules::default())
+        .await
+        .unwrap();
+
+    assert_eq!(reasons(&tx, id).await, ["self_trade"]);
+    assert_eq!(collections, [COLLECTION]);
+}
+
+#[tokio::test]
+async fn flags_both_legs_of_circular_trade() {
+    let Some(tx) = begin().await else { return };
+
+    let first = sale(&tx, BOB, ALICE, 0, usei(10)).await;
+    let second = sale(&tx, ALICE, BOB, 5, usei(10)).await;
+    flag_suspicious(&tx, Some(second), &WashTradeRules::default())
+        .await
+        .unwrap();
+
+    assert_eq!(reasons(&tx, first).await, ["circular_trade"]);
+    assert_eq!(reasons(&tx, second).await, ["circular_trade"]);
+}
+
+#[tokio::test]
+async fn ignores_reverse_trade_outside_window() {
+    let Some(tx) = begin().await else { return };
+
+    let first = sale(&tx, BOB, ALICE, 0, usei(10)).await;
+    let second = sale(&tx, ALICE, BOB, 48, usei(10)).await;
+    let third = sale(&tx, CAROL, BOB, 49, usei(10)).await;
+    flag_suspicious(&tx, None, &WashTradeRules::default())
+        .await
+        .unwrap();
+
+    assert!(reasons(&tx, first).await.is_empty());
+    assert!(reasons(&tx, second).await.is_empty());
+    assert!(reasons(&tx, third).await.is_empty());
+}
+
+#[tokio::test]
+async fn flags_off_floor_against_nearest_snapshot() {
+    let Some(tx) = begin().await else { return };
+
+    snapshot(&tx, 0, usei(1)).await;
+    snapshot(&tx, 100, usei(100)).await;
+
+    // 50 sei is off a floor of 1 but not of 100, only the snapshot closest to the sale counts
+    let early = sale(&tx, BOB, ALICE, 10, usei(50)).await;
+    let late = sale(&tx, CAROL, BOB, 90, usei(50)).await;
+    flag_suspicious(&tx, None, &WashTradeRules::default())
+        .await
+        .unwrap();
+
+    assert_eq!(reasons(&tx, early).await, ["off_floor_price"]);
+    assert!(reasons(&tx, late).await.is_empty());
+}
+
+#[tokio::test]
+async fn skips_off_floor_without_snapshot() {
+    let Some(tx) = begin().await else { return };
+
+    let id = sale(&tx, BOB, ALICE, 0, usei(1_000_000)).await;
+    flag_suspicious(&tx, Some(id), &WashTradeRules::default())
+        .await
+        .unwrap();
+
+    assert!(reasons(&tx, id).await.is_empty());
+}
@@ -3,10 +3,36 @@ module.exports = {
     {
       name: "pallet-stream",
       script: "./target/release/pallet-stream",
+      kill_timeout: 120000,
+      env: { HEALTH_PORT: 9101 },
     },
     {
       name: "cw721-stream",
       script: "./target/release/cw721-stream",
+      kill_timeout: 120000,
+      env: { HEALTH_PORT: 9102 },
+    },
+    {
+      name: "evm-stream",
+      script: "./target/release/evm-stream",
+      kill_timeout: 120000,
+      env: { HEALTH_PORT: 9103 },
+    },
+    {
+      name: "collection-stats",
+      script: "./target/release/collection-stats",
+    },
+    {
+      name: "collection-snapshot",
+      script: "./target/release/collection-snapshot",
+    },
+    {
+      name: "royalty",
+      script: "./target/release/royalty",
+    },
+    {
+      name: "rarity",
+      script: "./target/release/rarity",
     },
   ],
 };
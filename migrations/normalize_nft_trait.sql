@@ -0,0 +1,12 @@
+-- values and display types used to be stored as json, so strings kept their quotes
+UPDATE "nft_trait"
+SET "value" = "value"::jsonb #>> '{}'
+WHERE "value" ~ '^".*"$';
+
+UPDATE "nft_trait"
+SET "display_type" = "display_type"::jsonb #>> '{}'
+WHERE "display_type" ~ '^".*"$';
+
+UPDATE "nft_trait"
+SET "numeric_value" = "value"::numeric
+WHERE "value" ~ '^-?[0-9]+(\.[0-9]+)?([eE][-+]?[0-9]+)?$';
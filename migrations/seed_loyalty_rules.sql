@@ -0,0 +1,17 @@
+-- default rules, sales keep the historical 1 point per whole token for both sides; the indexers
+-- fall back to the same rates (PointRate::baseline) when no rule applies
+INSERT INTO "loyalty_rule" ("kind", "base_point", "point_per_unit", "multiplier")
+SELECT "v"."kind"::"loyalty_point_kind", "v"."base_point", "v"."point_per_unit", 1
+FROM (VALUES
+    ('buy', 0, 1),
+    ('sell', 0, 1),
+    ('bid', 0, 0.1),
+    ('xp', 1, 0)
+) AS "v"("kind", "base_point", "point_per_unit")
+WHERE NOT EXISTS (
+    SELECT 1 FROM "loyalty_rule" "r"
+    WHERE "r"."kind" = "v"."kind"::"loyalty_point_kind"
+    AND "r"."marketplace" IS NULL
+    AND "r"."collection_address" IS NULL
+    AND "r"."season_id" IS NULL
+);
@@ -0,0 +1,184 @@
+use base64::{prelude::BASE64_STANDARD, Engine};
+use chrono::Duration;
+use deadpool_redis::redis::AsyncCommands;
+use serde::Serialize;
+
+use crate::{
+    error::{AppError, AppResult},
+    extractors::{Claims, RedisConnection, SubClaims},
+};
+
+static ADDRESS_PREFIX: &str = "sei";
+static NONCE_TTL: u64 = 300;
+static ACCESS_TOKEN_LIFETIME: Duration = Duration::minutes(15);
+static REFRESH_TOKEN_LIFETIME: Duration = Duration::days(30);
+
+#[derive(Serialize, Debug)]
+pub struct AuthTokens {
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
+pub fn validate_address(address: &str) -> AppResult<()> {
+    match bech32::decode(address) {
+        Ok((hrp, _)) if hrp.as_str() == ADDRESS_PREFIX => Ok(()),
+        _ => Err(AppError::BadRequestError(format!(
+            "{} is not a sei address",
+            address
+        ))),
+    }
+}
+
+pub fn login_message(address: &str, nonce: &str) -> String {
+    format!(
+        "Sign in to mrktoxide\n\nAddress: {}\nNonce: {}",
+        address, nonce
+    )
+}
+
+// nonces are single use and keyed by their own value, requesting one never replaces another
+// outstanding nonce of the address
+pub async fn create_nonce(redis: &mut RedisConnection, address: &str) -> AppResult<String> {
+    let nonce = uuid::Uuid::new_v4().simple().to_string();
+
+    redis
+        .set_ex::<_, _, ()>(nonce_key(&nonce), address, NONCE_TTL)
+        .await?;
+
+    Ok(nonce)
+}
+
+pub async fn consume_nonce(
+    redis: &mut RedisConnection,
+    address: &str,
+    nonce: &str,
+) -> AppResult<()> {
+    let owner = redis.get_del::<_, Option<String>>(nonce_key(nonce)).await?;
+
+    if owner.as_deref() != Some(address) {
+        return Err(AppError::UnauthorizedError(
+            "Nonce expired or missing".into(),
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn verify_signature(
+    address: &str,
+    message: &str,
+    pub_key: &str,
+    signature: &str,
+) -> AppResult<()> {
+    let invalid = || AppError::UnauthorizedError("Invalid signature".into());
+
+    let pub_key = BASE64_STANDARD.decode(pub_key).map_err(|_| invalid())?;
+    let signature = BASE64_STANDARD.decode(signature).map_err(|_| invalid())?;
+
+    service::verify_arbitrary_signature(address, message.as_bytes(), &pub_key, &signature)
+        .map_err(|e| AppError::UnauthorizedError(e.to_string()))
+}
+
+// an access token belongs to the session of the refresh token issued with it
+pub async fn issue_tokens(redis: &mut RedisConnection, address: &str) -> AppResult<AuthTokens> {
+    let sub_claims = SubClaims::new(address.to_owned(), REFRESH_TOKEN_LIFETIME);
+    let claims = Claims::new(
+        address.to_owned(),
+        sub_claims.jti.to_owned(),
+        ACCESS_TOKEN_LIFETIME,
+    );
+    let ttl = REFRESH_TOKEN_LIFETIME.num_seconds();
+
+    redis
+        .set_ex::<_, _, ()>(refresh_token_key(&sub_claims.jti), address, ttl as u64)
+        .await?;
+    redis
+        .sadd::<_, _, ()>(sessions_key(address), &sub_claims.jti)
+        .await?;
+    redis.expire::<_, ()>(sessions_key(address), ttl).await?;
+
+    Ok(AuthTokens {
+        access_token: claims.encode()?,
+        refresh_token: sub_claims.encode()?,
+    })
+}
+
+// a refresh token is valid once, refreshing rotates it
+pub async fn revoke_refresh_token(
+    redis: &mut RedisConnection,
+    SubClaims { sub, jti, .. }: &SubClaims,
+) -> AppResult<()> {
+    let revoked = redis.del::<_, u32>(refresh_token_key(jti)).await?;
+
+    redis.srem::<_, _, ()>(sessions_key(sub), jti).await?;
+
+    if revoked == 0 {
+        return Err(AppError::UnauthorizedError("Revoked token".into()));
+    }
+
+    revoke_access_tokens(redis, jti).await?;
+
+    Ok(())
+}
+
+pub async fn revoke_sessions(redis: &mut RedisConnection, address: &str) -> AppResult<()> {
+    let jtis = redis
+        .smembers::<_, Vec<String>>(sessions_key(address))
+        .await?;
+
+    let mut keys = jtis
+        .iter()
+        .map(|jti| refresh_token_key(jti))
+        .collect::<Vec<_>>();
+
+    keys.push(sessions_key(address));
+
+    redis.del::<_, ()>(keys).await?;
+
+    for jti in &jtis {
+        revoke_access_tokens(redis, jti).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn ensure_session_active(redis: &mut RedisConnection, session: &str) -> AppResult<()> {
+    let revoked = redis
+        .exists::<_, bool>(revoked_session_key(session))
+        .await?;
+
+    if revoked {
+        return Err(AppError::UnauthorizedError("Revoked token".into()));
+    }
+
+    Ok(())
+}
+
+// access tokens stay stateless, the mark only has to outlive the longest lived one
+async fn revoke_access_tokens(redis: &mut RedisConnection, session: &str) -> AppResult<()> {
+    redis
+        .set_ex::<_, _, ()>(
+            revoked_session_key(session),
+            1,
+            ACCESS_TOKEN_LIFETIME.num_seconds() as u64,
+        )
+        .await?;
+
+    Ok(())
+}
+
+fn nonce_key(nonce: &str) -> String {
+    format!("auth:nonce:{}", nonce)
+}
+
+fn revoked_session_key(session: &str) -> String {
+    format!("auth:revoked:{}", session)
+}
+
+fn refresh_token_key(jti: &str) -> String {
+    format!("auth:refresh:{}", jti)
+}
+
+fn sessions_key(address: &str) -> String {
+    format!("auth:sessions:{}", address)
+}
@@ -0,0 +1,34 @@
+use deadpool_redis::redis::AsyncCommands;
+use serde::{de::DeserializeOwned, Serialize};
+use std::future::Future;
+
+use crate::{
+    error::{AppError, AppResult},
+    extractors::RedisConnection,
+};
+
+pub async fn cached<T, F, Fut>(
+    redis: &mut RedisConnection,
+    key: &str,
+    ttl: u64,
+    fetch: F,
+) -> AppResult<T>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = AppResult<T>>,
+{
+    let hit = redis.get::<_, Option<String>>(key).await?;
+
+    if let Some(hit) = hit.and_then(|hit| serde_json::from_str::<T>(&hit).ok()) {
+        return Ok(hit);
+    }
+
+    let value = fetch().await?;
+
+    let raw = serde_json::to_string(&value).map_err(|e| AppError::InternalError(e.to_string()))?;
+
+    redis.set_ex::<_, _, ()>(key, raw, ttl).await?;
+
+    Ok(value)
+}
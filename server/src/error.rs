@@ -5,7 +5,7 @@ use axum::{
     Json,
 };
 use database::error::DbErr;
-use deadpool_redis::PoolError;
+use deadpool_redis::{redis::RedisError, PoolError};
 use serde_json::json;
 use service::ServiceError;
 
@@ -19,6 +19,9 @@ pub enum AppError {
     #[error("{0}")]
     BadRequestError(String),
 
+    #[error("{0}")]
+    NotFoundError(String),
+
     #[error("{0}")]
     InternalError(String),
 
@@ -31,6 +34,9 @@ pub enum AppError {
     #[error(transparent)]
     RedisPoolError(#[from] PoolError),
 
+    #[error(transparent)]
+    RedisError(#[from] RedisError),
+
     #[error(transparent)]
     ValidationError(#[from] validator::ValidationErrors),
 
@@ -55,6 +61,10 @@ impl IntoResponse for AppError {
                 StatusCode::BAD_REQUEST,
                 to_json(StatusCode::BAD_REQUEST, reason),
             ),
+            AppError::NotFoundError(reason) => (
+                StatusCode::NOT_FOUND,
+                to_json(StatusCode::NOT_FOUND, reason),
+            ),
             AppError::InternalError(reason) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 to_json(StatusCode::INTERNAL_SERVER_ERROR, reason),
@@ -67,7 +77,7 @@ impl IntoResponse for AppError {
                         status_code,
                         format!(
                             "Error occured when sending http request, reason: {}",
-                            http_error.to_string()
+                            http_error
                         ),
                     ),
                 )
@@ -80,6 +90,10 @@ impl IntoResponse for AppError {
                 StatusCode::INTERNAL_SERVER_ERROR,
                 to_json(StatusCode::INTERNAL_SERVER_ERROR, pool_error.to_string()),
             ),
+            AppError::RedisError(redis_error) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                to_json(StatusCode::INTERNAL_SERVER_ERROR, redis_error.to_string()),
+            ),
             AppError::ValidationError(_) => {
                 let message = format!("Input validation error: [{self}]").replace('\n', ", ");
                 (
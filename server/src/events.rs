@@ -0,0 +1,38 @@
+use deadpool_redis::redis::{Client, RedisError};
+use futures_util::StreamExt;
+use service::{MarketEvent, MARKET_EVENT_CHANNEL};
+use std::time::Duration;
+use tokio::sync::broadcast::Sender;
+
+static RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+// a single redis subscription per server, every websocket/sse client listens on the broadcast side
+pub async fn forward_market_events(redis_url: String, sender: Sender<MarketEvent>) {
+    loop {
+        if let Err(error) = subscribe(&redis_url, &sender).await {
+            eprintln!("unexpected error when subscribe market events {}", error);
+        }
+
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+async fn subscribe(redis_url: &str, sender: &Sender<MarketEvent>) -> Result<(), RedisError> {
+    let mut pubsub = Client::open(redis_url)?.get_async_pubsub().await?;
+
+    pubsub.subscribe(MARKET_EVENT_CHANNEL).await?;
+
+    let mut messages = pubsub.on_message();
+
+    while let Some(message) = messages.next().await {
+        let payload = message.get_payload::<String>()?;
+
+        match serde_json::from_str::<MarketEvent>(&payload) {
+            // no subscriber connected is not an error
+            Ok(event) => _ = sender.send(event),
+            Err(error) => eprintln!("unexpected error can not parse market event {}", error),
+        }
+    }
+
+    Ok(())
+}
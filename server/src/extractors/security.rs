@@ -1,11 +1,16 @@
-use axum::{async_trait, extract::FromRequestParts, http::request::Parts, RequestPartsExt};
+use axum::{
+    async_trait,
+    extract::{FromRef, FromRequestParts},
+    http::request::Parts,
+    RequestPartsExt,
+};
 use axum_extra::{
     headers::{authorization::Bearer, Authorization},
     TypedHeader,
 };
 use chrono::Utc;
 use jsonwebtoken::errors::ErrorKind;
-use jsonwebtoken::{DecodingKey, Validation};
+use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
 
 use crate::error::{AppError, AppResult};
@@ -14,12 +19,15 @@ use crate::error::{AppError, AppResult};
 pub struct Claims {
     pub exp: u32,
     pub address: String,
+    // jti of the refresh token issued together, revoking it revokes this token too
+    pub sid: String,
 }
 
 #[derive(Deserialize, Serialize)]
 pub struct SubClaims {
     pub exp: u32,
     pub sub: String,
+    pub jti: String,
 }
 
 pub struct Guard(pub Claims);
@@ -28,10 +36,11 @@ pub struct Guard(pub Claims);
 impl<S> FromRequestParts<S> for Guard
 where
     S: Send + Sync,
+    deadpool_redis::Pool: FromRef<S>,
 {
     type Rejection = AppError;
 
-    async fn from_request_parts(parts: &mut Parts, _state: &S) -> AppResult<Self> {
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> AppResult<Self> {
         let access_secret = std::env::var("JWT_SECRET").expect("JWT_SECRET must be set.");
 
         let bearer = parts
@@ -39,7 +48,7 @@ where
             .await
             .map_err(|_| AppError::UnauthorizedError("Missing Authorization".into()))?;
 
-        jsonwebtoken::decode::<Claims>(
+        let claims = jsonwebtoken::decode::<Claims>(
             bearer.token(),
             &DecodingKey::from_secret(access_secret.as_bytes()),
             &Validation::default(),
@@ -47,15 +56,22 @@ where
         .map_err(|err| match err.kind() {
             ErrorKind::ExpiredSignature => AppError::UnauthorizedError("Expired token".into()),
             _ => AppError::UnauthorizedError("Invalid token".into()),
-        })
-        .map(|token_data| Self(token_data.claims))
+        })?
+        .claims;
+
+        let mut redis = deadpool_redis::Pool::from_ref(state).get().await?;
+
+        crate::auth::ensure_session_active(&mut redis, &claims.sid).await?;
+
+        Ok(Self(claims))
     }
 }
 
 impl Claims {
-    pub fn new(address: String, expired: chrono::Duration) -> Self {
+    pub fn new(address: String, sid: String, expired: chrono::Duration) -> Self {
         Self {
             address,
+            sid,
             exp: Utc::now().checked_add_signed(expired).unwrap().timestamp() as u32,
         }
     }
@@ -66,6 +82,47 @@ impl SubClaims {
         Self {
             sub: address,
             exp: Utc::now().checked_add_signed(expired).unwrap().timestamp() as u32,
+            jti: uuid::Uuid::new_v4().simple().to_string(),
         }
     }
 }
+
+impl Claims {
+    pub fn encode(&self) -> AppResult<String> {
+        let access_secret = std::env::var("JWT_SECRET").expect("JWT_SECRET must be set.");
+
+        encode_token(self, &access_secret)
+    }
+}
+
+impl SubClaims {
+    pub fn encode(&self) -> AppResult<String> {
+        encode_token(self, &refresh_secret())
+    }
+
+    pub fn decode(token: &str) -> AppResult<Self> {
+        jsonwebtoken::decode::<SubClaims>(
+            token,
+            &DecodingKey::from_secret(refresh_secret().as_bytes()),
+            &Validation::default(),
+        )
+        .map_err(|err| match err.kind() {
+            ErrorKind::ExpiredSignature => AppError::UnauthorizedError("Expired token".into()),
+            _ => AppError::UnauthorizedError("Invalid token".into()),
+        })
+        .map(|token_data| token_data.claims)
+    }
+}
+
+fn refresh_secret() -> String {
+    std::env::var("JWT_REFRESH_SECRET").expect("JWT_REFRESH_SECRET must be set.")
+}
+
+fn encode_token(claims: &impl Serialize, secret: &str) -> AppResult<String> {
+    jsonwebtoken::encode(
+        &Header::default(),
+        claims,
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )
+    .map_err(|e| AppError::InternalError(e.to_string()))
+}
@@ -6,6 +6,9 @@ use axum::{
 };
 use database::{ConnectOptions, Database, DatabaseConnection};
 use deadpool_redis::{Config, Runtime};
+use service::{MarketEvent, PriceOracle, StaticPriceOracle};
+use std::sync::Arc;
+use tokio::sync::broadcast;
 
 pub type RedisConnection = deadpool_redis::Connection;
 
@@ -16,6 +19,8 @@ pub struct Postgres(pub DatabaseConnection);
 pub struct AppState {
     pub db: DatabaseConnection,
     pub redis_pool: deadpool_redis::Pool,
+    pub market_events: broadcast::Sender<MarketEvent>,
+    pub price_oracle: Arc<dyn PriceOracle>,
 }
 
 #[async_trait]
@@ -72,9 +77,24 @@ impl AppState {
             .create_pool(Some(Runtime::Tokio1))
             .unwrap();
 
+        let (market_events, _) = broadcast::channel(1024);
+
+        tokio::spawn(crate::events::forward_market_events(
+            redis_url.to_owned(),
+            market_events.clone(),
+        ));
+
+        // without a price file only sei amounts are known, usd values stay empty
+        let price_oracle: Arc<dyn PriceOracle> = match std::env::var("PRICE_ORACLE_FILE") {
+            Ok(path) => Arc::new(StaticPriceOracle::from_file(&path).unwrap()),
+            Err(_) => Arc::new(StaticPriceOracle::default()),
+        };
+
         Self {
             db: database_connection,
             redis_pool,
+            market_events,
+            price_oracle,
         }
     }
 }
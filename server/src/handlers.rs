@@ -1,7 +1,27 @@
+mod auth;
+mod get_activities;
+mod get_collection_royalties;
+mod get_collection_snapshots;
+mod get_collection_traits;
 mod get_collections;
+mod get_leaderboard;
 mod get_listed_nfts;
+mod get_market_events;
+mod get_nft;
 mod get_user_nfts;
+mod get_user_points;
+mod me;
 
+pub use auth::*;
+pub use get_activities::*;
+pub use get_collection_royalties::*;
+pub use get_collection_snapshots::*;
+pub use get_collection_traits::*;
 pub use get_collections::*;
+pub use get_leaderboard::*;
 pub use get_listed_nfts::*;
+pub use get_market_events::*;
+pub use get_nft::*;
 pub use get_user_nfts::*;
+pub use get_user_points::*;
+pub use me::*;
@@ -0,0 +1,126 @@
+use crate::{
+    auth::{self, AuthTokens},
+    error::AppError,
+    extractors::{AppState, Guard, Redis, SubClaims, ValidatedPayload},
+};
+use axum::{extract::State, Json};
+use database::repositories;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+pub async fn request_nonce(
+    Redis(mut redis): Redis,
+    ValidatedPayload(payload): ValidatedPayload<NonceRequest>,
+) -> Result<Json<NonceResponse>, AppError> {
+    let NonceRequest { address } = payload;
+
+    auth::validate_address(&address)?;
+
+    let nonce = auth::create_nonce(&mut redis, &address).await?;
+
+    Ok(Json(NonceResponse {
+        message: auth::login_message(&address, &nonce),
+        nonce,
+    }))
+}
+
+pub async fn login(
+    State(AppState { db, .. }): State<AppState>,
+    Redis(mut redis): Redis,
+    ValidatedPayload(payload): ValidatedPayload<LoginRequest>,
+) -> Result<Json<LoginResponse>, AppError> {
+    let LoginRequest {
+        address,
+        nonce,
+        pub_key,
+        signature,
+    } = payload;
+
+    auth::consume_nonce(&mut redis, &address, &nonce).await?;
+
+    auth::verify_signature(
+        &address,
+        &auth::login_message(&address, &nonce),
+        &pub_key,
+        &signature,
+    )?;
+
+    let user = repositories::user::find_or_create(&db, &address).await?;
+
+    let tokens = auth::issue_tokens(&mut redis, &address).await?;
+
+    Ok(Json(LoginResponse {
+        tokens,
+        is_new_user: user.is_new_user,
+    }))
+}
+
+pub async fn refresh_token(
+    Redis(mut redis): Redis,
+    ValidatedPayload(payload): ValidatedPayload<RefreshTokenRequest>,
+) -> Result<Json<AuthTokens>, AppError> {
+    let sub_claims = SubClaims::decode(&payload.refresh_token)?;
+
+    auth::revoke_refresh_token(&mut redis, &sub_claims).await?;
+
+    let tokens = auth::issue_tokens(&mut redis, &sub_claims.sub).await?;
+
+    Ok(Json(tokens))
+}
+
+pub async fn logout(
+    Redis(mut redis): Redis,
+    ValidatedPayload(payload): ValidatedPayload<RefreshTokenRequest>,
+) -> Result<Json<()>, AppError> {
+    let sub_claims = SubClaims::decode(&payload.refresh_token)?;
+
+    auth::revoke_refresh_token(&mut redis, &sub_claims).await?;
+
+    Ok(Json(()))
+}
+
+pub async fn revoke_sessions(
+    Guard(claims): Guard,
+    Redis(mut redis): Redis,
+) -> Result<Json<()>, AppError> {
+    auth::revoke_sessions(&mut redis, &claims.address).await?;
+
+    Ok(Json(()))
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct NonceRequest {
+    #[validate(length(min = 1))]
+    pub address: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct NonceResponse {
+    pub nonce: String,
+    pub message: String,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct LoginRequest {
+    #[validate(length(min = 1))]
+    pub address: String,
+    #[validate(length(min = 1))]
+    pub nonce: String,
+    #[validate(length(min = 1))]
+    pub pub_key: String,
+    #[validate(length(min = 1))]
+    pub signature: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct LoginResponse {
+    #[serde(flatten)]
+    pub tokens: AuthTokens,
+    pub is_new_user: bool,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct RefreshTokenRequest {
+    #[validate(length(min = 1))]
+    pub refresh_token: String,
+}
@@ -0,0 +1,161 @@
+use crate::{error::AppError, extractors::AppState};
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::DateTime;
+use database::{
+    repositories::{
+        self,
+        nft_activity::{ActivityCursor, ActivityFilter, ActivityWithNft},
+    },
+    sea_orm_active_enums::{Marketplace, NftActivityKind},
+};
+use serde::Deserialize;
+use server::{empty_string_as_none, CursorResponse, Quoted};
+
+pub async fn get_activities(
+    State(AppState {
+        db, price_oracle, ..
+    }): State<AppState>,
+    Query(query): Query<GetActivitiesQuery>,
+) -> Result<Json<CursorResponse<Quoted<ActivityWithNft>>>, AppError> {
+    let GetActivitiesQuery {
+        collection_address,
+        token_id,
+        wallet_address,
+        kind,
+        market,
+        from,
+        to,
+        cursor,
+        take,
+    } = query;
+
+    let cursor = cursor.map(|cursor| decode_cursor(&cursor)).transpose()?;
+
+    let to_date = |timestamp: i64| {
+        DateTime::from_timestamp(timestamp, 0)
+            .ok_or(AppError::BadRequestError("Invalid timestamp".into()))
+    };
+
+    let filter = ActivityFilter {
+        collection_address,
+        token_id,
+        wallet_address,
+        event_kind: kind.map(|kind| kind.to_activity_kind()),
+        marketplace: market.map(|market| market.to_marketplace()),
+        from: from.map(to_date).transpose()?,
+        to: to.map(to_date).transpose()?,
+    };
+
+    let (activities, next_cursor) =
+        repositories::nft_activity::find_feed(&db, filter, cursor, take.unwrap_or(20)).await?;
+
+    Ok(Json(CursorResponse {
+        next_cursor: next_cursor.map(|cursor| encode_cursor(&cursor)),
+        data: activities
+            .into_iter()
+            .map(|activity| Quoted {
+                quote: Some(price_oracle.quote(activity.price, &activity.denom)),
+                data: activity,
+            })
+            .collect(),
+    }))
+}
+
+fn encode_cursor(ActivityCursor { date, id }: &ActivityCursor) -> String {
+    format!("{}_{}", date.timestamp_micros(), id)
+}
+
+fn decode_cursor(cursor: &str) -> Result<ActivityCursor, AppError> {
+    let invalid = || AppError::BadRequestError("Invalid cursor".into());
+
+    let (date, id) = cursor.split_once('_').ok_or_else(invalid)?;
+
+    Ok(ActivityCursor {
+        date: date
+            .parse::<i64>()
+            .ok()
+            .and_then(DateTime::from_timestamp_micros)
+            .ok_or_else(invalid)?,
+        id: id.parse::<i32>().map_err(|_| invalid())?,
+    })
+}
+
+#[derive(Deserialize, Debug)]
+pub struct GetActivitiesQuery {
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    collection_address: Option<String>,
+
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    token_id: Option<String>,
+
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    wallet_address: Option<String>,
+
+    kind: Option<ActivityKind>,
+
+    market: Option<Market>,
+
+    from: Option<i64>,
+
+    to: Option<i64>,
+
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    cursor: Option<String>,
+
+    take: Option<u16>,
+}
+
+#[derive(Deserialize, Debug)]
+enum ActivityKind {
+    #[serde(rename(deserialize = "list"))]
+    List,
+
+    #[serde(rename(deserialize = "delist"))]
+    Delist,
+
+    #[serde(rename(deserialize = "sale"))]
+    Sale,
+
+    #[serde(rename(deserialize = "make_offer"))]
+    MakeOffer,
+
+    #[serde(rename(deserialize = "cancel_offer"))]
+    CancelOffer,
+}
+
+impl ActivityKind {
+    fn to_activity_kind(&self) -> NftActivityKind {
+        match self {
+            Self::List => NftActivityKind::List,
+            Self::Delist => NftActivityKind::Delist,
+            Self::Sale => NftActivityKind::Sale,
+            Self::MakeOffer => NftActivityKind::MakeOffer,
+            Self::CancelOffer => NftActivityKind::CancelOffer,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+enum Market {
+    #[serde(rename(deserialize = "mrkt"))]
+    Mrkt,
+
+    #[serde(rename(deserialize = "pallet"))]
+    Pallet,
+
+    #[serde(rename(deserialize = "evm"))]
+    Evm,
+}
+
+impl Market {
+    fn to_marketplace(&self) -> Marketplace {
+        match self {
+            Self::Mrkt => Marketplace::Mrkt,
+            Self::Pallet => Marketplace::Pallet,
+            Self::Evm => Marketplace::Evm,
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{error::AppError, extractors::AppState};
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use database::{prelude::Decimal, repositories};
+use serde::{Deserialize, Serialize};
+use service::SEI_DENOM;
+
+pub async fn get_collection_royalties(
+    State(AppState { db, .. }): State<AppState>,
+    Path(collection_address): Path<String>,
+    Query(query): Query<GetCollectionRoyaltiesQuery>,
+) -> Result<Json<CollectionRoyalties>, AppError> {
+    let to_date = |timestamp: i64| {
+        DateTime::from_timestamp(timestamp, 0).ok_or(AppError::BadRequestError(format!(
+            "{} is not a valid timestamp",
+            timestamp
+        )))
+    };
+
+    // an explicit window wins over the range
+    let from = match query.from {
+        Some(from) => Some(to_date(from)?),
+        None => query
+            .range
+            .unwrap_or_default()
+            .to_duration()
+            .map(|duration| Utc::now() - duration),
+    };
+    let to = query.to.map(to_date).transpose()?;
+
+    let earnings = repositories::transaction::find_royalty_earnings(
+        &db,
+        &collection_address,
+        query.denom.as_deref().unwrap_or(SEI_DENOM),
+        (from, to),
+    )
+    .await?;
+
+    let mut royalties = CollectionRoyalties {
+        sales: 0,
+        volume: Decimal::ZERO,
+        royalty_amount: Decimal::ZERO,
+        marketplace_fee: Decimal::ZERO,
+        data: Vec::with_capacity(earnings.len()),
+    };
+
+    for earning in earnings {
+        royalties.sales += earning.sales;
+        royalties.volume += earning.volume;
+        royalties.royalty_amount += earning.royalty_amount;
+        royalties.marketplace_fee += earning.marketplace_fee;
+
+        royalties.data.push(CollectionRoyaltiesPoint {
+            date: earning.date.timestamp(),
+            sales: earning.sales,
+            volume: earning.volume,
+            royalty_amount: earning.royalty_amount,
+            marketplace_fee: earning.marketplace_fee,
+        });
+    }
+
+    Ok(Json(royalties))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct GetCollectionRoyaltiesQuery {
+    range: Option<Range>,
+    from: Option<i64>,
+    to: Option<i64>,
+    denom: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct CollectionRoyalties {
+    pub sales: i64,
+    pub volume: Decimal,
+    pub royalty_amount: Decimal,
+    pub marketplace_fee: Decimal,
+    pub data: Vec<CollectionRoyaltiesPoint>,
+}
+
+#[derive(Serialize)]
+pub struct CollectionRoyaltiesPoint {
+    pub date: i64,
+    pub sales: i64,
+    pub volume: Decimal,
+    pub royalty_amount: Decimal,
+    pub marketplace_fee: Decimal,
+}
+
+#[derive(Deserialize, Debug, Default)]
+enum Range {
+    #[serde(rename(deserialize = "24h"))]
+    _24h,
+
+    #[serde(rename(deserialize = "7d"))]
+    _7d,
+
+    #[serde(rename(deserialize = "30d"))]
+    #[default]
+    _30d,
+
+    #[serde(rename(deserialize = "all"))]
+    All,
+}
+
+impl Range {
+    fn to_duration(&self) -> Option<Duration> {
+        match self {
+            Self::_24h => Some(Duration::days(1)),
+            Self::_7d => Some(Duration::days(7)),
+            Self::_30d => Some(Duration::days(30)),
+            Self::All => None,
+        }
+    }
+}
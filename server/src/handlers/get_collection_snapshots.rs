@@ -0,0 +1,125 @@
+use crate::{error::AppError, extractors::AppState};
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{Duration, Utc};
+use database::{prelude::Decimal, repositories};
+use serde::{Deserialize, Serialize};
+
+pub async fn get_collection_snapshots(
+    State(AppState { db, .. }): State<AppState>,
+    Path(collection_address): Path<String>,
+    Query(query): Query<GetCollectionSnapshotsQuery>,
+) -> Result<Json<CollectionSnapshots>, AppError> {
+    let now = Utc::now();
+
+    let from = query
+        .range
+        .unwrap_or_default()
+        .to_duration()
+        .map(|duration| now - duration);
+
+    let data = repositories::collection_snapshot::find_by_collection_address(
+        &db,
+        &collection_address,
+        from,
+    )
+    .await?;
+
+    let latest =
+        repositories::collection_snapshot::find_latest_before(&db, &collection_address, now)
+            .await?;
+
+    let before_24h = repositories::collection_snapshot::find_latest_before(
+        &db,
+        &collection_address,
+        now - Duration::days(1),
+    )
+    .await?;
+
+    let before_7d = repositories::collection_snapshot::find_latest_before(
+        &db,
+        &collection_address,
+        now - Duration::days(7),
+    )
+    .await?;
+
+    let change = |past: &Option<_>, field: fn(&_) -> Decimal| {
+        latest
+            .as_ref()
+            .zip(past.as_ref())
+            .and_then(|(latest, past)| percent_change(field(latest), field(past)))
+    };
+
+    Ok(Json(CollectionSnapshots {
+        floor_change_24h: change(&before_24h, |s| s.floor),
+        floor_change_7d: change(&before_7d, |s| s.floor),
+        volume_change_24h: change(&before_24h, |s| s.volume_of_24h),
+        volume_change_7d: change(&before_7d, |s| s.volume_of_24h),
+        data: data
+            .into_iter()
+            .map(|snapshot| CollectionSnapshotPoint {
+                date: snapshot.date.timestamp(),
+                floor: snapshot.floor,
+                volume_of_24h: snapshot.volume_of_24h,
+            })
+            .collect(),
+    }))
+}
+
+fn percent_change(current: Decimal, past: Decimal) -> Option<Decimal> {
+    if past.is_zero() {
+        return None;
+    }
+
+    Some(((current - past) / past * Decimal::ONE_HUNDRED).round_dp(2))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct GetCollectionSnapshotsQuery {
+    range: Option<Range>,
+}
+
+#[derive(Serialize)]
+pub struct CollectionSnapshots {
+    pub floor_change_24h: Option<Decimal>,
+    pub floor_change_7d: Option<Decimal>,
+    pub volume_change_24h: Option<Decimal>,
+    pub volume_change_7d: Option<Decimal>,
+    pub data: Vec<CollectionSnapshotPoint>,
+}
+
+#[derive(Serialize)]
+pub struct CollectionSnapshotPoint {
+    pub date: i64,
+    pub floor: Decimal,
+    pub volume_of_24h: Decimal,
+}
+
+#[derive(Deserialize, Debug, Default)]
+enum Range {
+    #[serde(rename(deserialize = "24h"))]
+    _24h,
+
+    #[serde(rename(deserialize = "7d"))]
+    #[default]
+    _7d,
+
+    #[serde(rename(deserialize = "30d"))]
+    _30d,
+
+    #[serde(rename(deserialize = "all"))]
+    All,
+}
+
+impl Range {
+    fn to_duration(&self) -> Option<Duration> {
+        match self {
+            Self::_24h => Some(Duration::days(1)),
+            Self::_7d => Some(Duration::days(7)),
+            Self::_30d => Some(Duration::days(30)),
+            Self::All => None,
+        }
+    }
+}
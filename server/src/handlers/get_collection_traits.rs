@@ -0,0 +1,33 @@
+use crate::{
+    cache,
+    error::AppError,
+    extractors::{AppState, Redis},
+};
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use database::repositories::{self, nft_trait::TraitSummary};
+use service::collection_traits_cache_key;
+
+static COLLECTION_TRAITS_TTL: u64 = 300;
+
+pub async fn get_collection_traits(
+    State(AppState { db, .. }): State<AppState>,
+    Redis(mut redis): Redis,
+    Path(collection_address): Path<String>,
+) -> Result<Json<Vec<TraitSummary>>, AppError> {
+    let traits = cache::cached(
+        &mut redis,
+        &collection_traits_cache_key(&collection_address),
+        COLLECTION_TRAITS_TTL,
+        || async {
+            repositories::nft_trait::find_catalog_by_collection(&db, &collection_address)
+                .await
+                .map_err(AppError::from)
+        },
+    )
+    .await?;
+
+    Ok(Json(traits))
+}
@@ -11,14 +11,17 @@ use database::{
     },
     Sort,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use server::{empty_string_as_none, PagedQuery, PaginatedReponse};
+use service::{Quote, SEI_DENOM};
 
 pub async fn get_collections(
-    State(AppState { db, .. }): State<AppState>,
+    State(AppState {
+        db, price_oracle, ..
+    }): State<AppState>,
     Query(query): Query<GetCollectionsQuery>,
     Query(paged_query): Query<PagedQuery>,
-) -> Result<Json<PaginatedReponse<CollectionWithStat>>, AppError> {
+) -> Result<Json<PaginatedReponse<CollectionWithQuotes>>, AppError> {
     use CollectionStatSelectOption::*;
 
     let GetCollectionsQuery {
@@ -32,10 +35,19 @@ pub async fn get_collections(
     let sort_by = sort_by.map(|s| s.to_stat_field()).unwrap_or(Volume);
 
     let cols = [
-        Address, Name, Image, Volume, FloorPrice, Sales, Listed, sort_by,
+        Address,
+        Name,
+        Image,
+        Volume,
+        FloorPrice,
+        Sales,
+        Listed,
+        FloorChange24h,
+        VolumeChange24h,
+        sort_by,
     ];
 
-    let collections = repositories::collection::find_collections_with_stats(
+    let (collections, total) = repositories::collection::find_collections_with_stats(
         &db,
         cols,
         search,
@@ -46,11 +58,31 @@ pub async fn get_collections(
 
     Ok(Json(PaginatedReponse {
         page,
-        total: 100,
-        data: collections,
+        total,
+        data: collections
+            .into_iter()
+            .map(|collection| CollectionWithQuotes {
+                floor_price_quote: collection
+                    .floor_price
+                    .map(|price| price_oracle.quote(price, SEI_DENOM)),
+                volume_quote: collection
+                    .volume
+                    .map(|volume| price_oracle.quote(volume, SEI_DENOM)),
+                collection,
+            })
+            .collect(),
     }))
 }
 
+// collection stats are kept in usei
+#[derive(Serialize, Debug)]
+pub struct CollectionWithQuotes {
+    #[serde(flatten)]
+    pub collection: CollectionWithStat,
+    pub floor_price_quote: Option<Quote>,
+    pub volume_quote: Option<Quote>,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct GetCollectionsQuery {
     #[serde(deserialize_with = "empty_string_as_none")]
@@ -61,12 +93,13 @@ pub struct GetCollectionsQuery {
     sort_by: Option<SortBy>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Default)]
 enum SortBy {
     #[serde(rename(deserialize = "1h"))]
     _1h,
 
     #[serde(rename(deserialize = "24h"))]
+    #[default]
     _24h,
 
     #[serde(rename(deserialize = "30d"))]
@@ -74,6 +107,12 @@ enum SortBy {
 
     #[serde(rename(deserialize = "all"))]
     All,
+
+    #[serde(rename(deserialize = "floor_change_24h"))]
+    FloorChange24h,
+
+    #[serde(rename(deserialize = "volume_change_24h"))]
+    VolumeChange24h,
 }
 
 impl SortBy {
@@ -83,12 +122,8 @@ impl SortBy {
             Self::_1h => CollectionStatSelectOption::VolumeOf1h,
             Self::_24h => CollectionStatSelectOption::VolumeOf24h,
             Self::_30d => CollectionStatSelectOption::VolumeOf30d,
+            Self::FloorChange24h => CollectionStatSelectOption::FloorChange24h,
+            Self::VolumeChange24h => CollectionStatSelectOption::VolumeChange24h,
         }
     }
 }
-
-impl Default for SortBy {
-    fn default() -> Self {
-        Self::_24h
-    }
-}
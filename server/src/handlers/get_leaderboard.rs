@@ -0,0 +1,91 @@
+use crate::{error::AppError, extractors::AppState};
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::{DateTime, Datelike, Duration, NaiveTime, Utc};
+use database::repositories::{self, user_point::LeaderboardEntry};
+use serde::Deserialize;
+use server::{PagedQuery, PaginatedReponse};
+
+pub async fn get_leaderboard(
+    State(AppState { db, .. }): State<AppState>,
+    Query(query): Query<GetLeaderboardQuery>,
+    Query(paged_query): Query<PagedQuery>,
+) -> Result<Json<PaginatedReponse<LeaderboardEntry>>, AppError> {
+    let PagedQuery { page, take } = paged_query;
+
+    let now = Utc::now();
+    let today = now.date_naive().and_time(NaiveTime::MIN).and_utc();
+
+    // daily and weekly boards follow the utc calendar, so everyone restarts at the same moment
+    let window = match query.period.unwrap_or_default() {
+        Period::Daily => (today, today + Duration::days(1)),
+        Period::Weekly => {
+            let monday = today - Duration::days(now.weekday().num_days_from_monday() as i64);
+
+            (monday, monday + Duration::days(7))
+        }
+        Period::Season => {
+            let season = match query.season_id {
+                Some(season_id) => repositories::loyalty_season::find_by_id(&db, season_id).await?,
+                None => repositories::loyalty_season::find_running(&db, now).await?,
+            }
+            .ok_or(AppError::NotFoundError("season not found".into()))?;
+
+            // a frozen season is served from its snapshot, late points can not move it
+            if season.snapshot_date.is_some() {
+                let (entries, total) = repositories::loyalty_season::find_snapshot(
+                    &db,
+                    season.id,
+                    (Some(page), Some(take)),
+                )
+                .await?;
+
+                return Ok(Json(PaginatedReponse {
+                    page,
+                    total,
+                    data: entries
+                        .into_iter()
+                        .map(|entry| LeaderboardEntry {
+                            wallet_address: entry.wallet_address,
+                            point: entry.point,
+                            rank: entry.rank as i64,
+                        })
+                        .collect(),
+                }));
+            }
+
+            (
+                DateTime::<Utc>::from(season.start_date),
+                DateTime::<Utc>::from(season.end_date),
+            )
+        }
+    };
+
+    let (entries, total) =
+        repositories::user_point::find_leaderboard(&db, window, (Some(page), Some(take))).await?;
+
+    Ok(Json(PaginatedReponse {
+        page,
+        total,
+        data: entries,
+    }))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct GetLeaderboardQuery {
+    period: Option<Period>,
+    season_id: Option<i32>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+enum Period {
+    #[serde(rename(deserialize = "daily"))]
+    Daily,
+    #[serde(rename(deserialize = "weekly"))]
+    #[default]
+    Weekly,
+    #[serde(rename(deserialize = "season"))]
+    Season,
+}
@@ -1 +1,90 @@
-pub async fn get_listed_nfts() {}
+use crate::{error::AppError, extractors::AppState};
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use database::{
+    repositories::{
+        self,
+        nft::{NftSortOption, NftWithListing, TraitFilter},
+    },
+    Sort,
+};
+use serde::Deserialize;
+use server::{json_string, PagedQuery, PaginatedReponse, Quoted};
+
+pub async fn get_listed_nfts(
+    State(AppState {
+        db, price_oracle, ..
+    }): State<AppState>,
+    Path(collection_address): Path<String>,
+    Query(query): Query<GetNftsQuery>,
+    Query(paged_query): Query<PagedQuery>,
+) -> Result<Json<PaginatedReponse<Quoted<NftWithListing>>>, AppError> {
+    let GetNftsQuery {
+        sort_by,
+        sort_direction,
+        traits,
+    } = query;
+
+    let PagedQuery { page, take } = paged_query;
+
+    let (nfts, total) = repositories::nft::find_listed_by_collection(
+        &db,
+        &collection_address,
+        &traits,
+        (Some(page), Some(take)),
+        (
+            sort_by.unwrap_or(NftSortBy::Price).to_sort_option(),
+            sort_direction.unwrap_or(Sort::Asc),
+        ),
+    )
+    .await?;
+
+    Ok(Json(PaginatedReponse {
+        page,
+        total,
+        data: nfts
+            .into_iter()
+            .map(|nft| Quoted {
+                quote: nft
+                    .price
+                    .zip(nft.denom.as_deref())
+                    .map(|(price, denom)| price_oracle.quote(price, denom)),
+                data: nft,
+            })
+            .collect(),
+    }))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct GetNftsQuery {
+    pub sort_by: Option<NftSortBy>,
+
+    pub sort_direction: Option<Sort>,
+
+    #[serde(default, deserialize_with = "json_string")]
+    pub traits: Vec<TraitFilter>,
+}
+
+#[derive(Deserialize, Debug)]
+pub enum NftSortBy {
+    #[serde(rename(deserialize = "price"))]
+    Price,
+
+    #[serde(rename(deserialize = "rarity"))]
+    Rarity,
+
+    #[serde(rename(deserialize = "listed_date"))]
+    ListedDate,
+}
+
+impl NftSortBy {
+    pub fn to_sort_option(&self) -> NftSortOption {
+        match self {
+            Self::Price => NftSortOption::Price,
+            Self::Rarity => NftSortOption::Rarity,
+            Self::ListedDate => NftSortOption::ListedDate,
+        }
+    }
+}
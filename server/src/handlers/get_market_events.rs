@@ -0,0 +1,107 @@
+use crate::extractors::AppState;
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Response,
+    },
+};
+use futures_util::{Stream, StreamExt};
+use serde::Deserialize;
+use server::empty_string_as_none;
+use service::{MarketEvent, MarketEventKind};
+use std::convert::Infallible;
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+use tokio_stream::wrappers::BroadcastStream;
+
+pub async fn get_market_events_ws(
+    State(AppState { market_events, .. }): State<AppState>,
+    Query(query): Query<MarketEventsQuery>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    let receiver = market_events.subscribe();
+
+    ws.on_upgrade(move |socket| push_market_events(socket, receiver, query))
+}
+
+pub async fn get_market_events_sse(
+    State(AppState { market_events, .. }): State<AppState>,
+    Query(query): Query<MarketEventsQuery>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let stream = BroadcastStream::new(market_events.subscribe()).filter_map(move |event| {
+        // a lagging client skips the events it missed instead of being disconnected
+        let event = event
+            .ok()
+            .filter(|event| query.matches(event))
+            .map(|event| {
+                Ok(Event::default()
+                    .event(serde_json::json!(event.kind).as_str().unwrap_or_default())
+                    .json_data(event)
+                    .unwrap_or_default())
+            });
+
+        async move { event }
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+async fn push_market_events(
+    mut socket: WebSocket,
+    mut receiver: Receiver<MarketEvent>,
+    query: MarketEventsQuery,
+) {
+    loop {
+        tokio::select! {
+            event = receiver.recv() => {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                };
+
+                if !query.matches(&event) {
+                    continue;
+                }
+
+                let message = Message::Text(serde_json::json!(event).to_string());
+
+                if socket.send(message).await.is_err() {
+                    break;
+                }
+            }
+            message = socket.recv() => {
+                // clients only listen, anything but a close frame is ignored
+                match message {
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => {}
+                }
+            }
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct MarketEventsQuery {
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub collection_address: Option<String>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub wallet_address: Option<String>,
+    pub kind: Option<MarketEventKind>,
+}
+
+impl MarketEventsQuery {
+    fn matches(&self, event: &MarketEvent) -> bool {
+        self.collection_address
+            .as_ref()
+            .is_none_or(|address| address == &event.collection_address)
+            && self
+                .wallet_address
+                .as_ref()
+                .is_none_or(|wallet| event.involves(wallet))
+            && self.kind.is_none_or(|kind| kind == event.kind)
+    }
+}
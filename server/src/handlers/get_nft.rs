@@ -0,0 +1,65 @@
+use crate::{
+    cache,
+    error::AppError,
+    extractors::{AppState, Redis},
+};
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use database::{
+    entities::nft_activity,
+    repositories::{self, nft::NftDetail},
+};
+use serde::Serialize;
+use server::{PagedQuery, PaginatedReponse};
+use service::nft_cache_key;
+
+static NFT_DETAIL_TTL: u64 = 600;
+
+pub async fn get_nft(
+    State(AppState { db, .. }): State<AppState>,
+    Redis(mut redis): Redis,
+    Path((collection_address, token_id)): Path<(String, String)>,
+    Query(paged_query): Query<PagedQuery>,
+) -> Result<Json<NftDetailResponse>, AppError> {
+    let PagedQuery { page, take } = paged_query;
+
+    let mut detail = cache::cached(
+        &mut redis,
+        &nft_cache_key(&collection_address, &token_id),
+        NFT_DETAIL_TTL,
+        || async {
+            repositories::nft::find_detail(&db, &collection_address, &token_id)
+                .await?
+                .ok_or(AppError::NotFoundError(format!(
+                    "nft {} of {} not found",
+                    token_id, collection_address
+                )))
+        },
+    )
+    .await?;
+
+    detail.drop_expired();
+
+    let (activities, total) =
+        repositories::nft_activity::find_by_nft_id(&db, detail.nft.id, (Some(page), Some(take)))
+            .await?;
+
+    Ok(Json(NftDetailResponse {
+        detail,
+        activities: PaginatedReponse {
+            page,
+            total,
+            data: activities,
+        },
+    }))
+}
+
+#[derive(Serialize)]
+pub struct NftDetailResponse {
+    #[serde(flatten)]
+    detail: NftDetail,
+
+    activities: PaginatedReponse<nft_activity::Model>,
+}
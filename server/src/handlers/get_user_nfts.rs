@@ -1 +1,53 @@
-pub async fn get_user_nfts() {}
+use crate::{error::AppError, extractors::AppState};
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use database::{repositories, repositories::nft::NftWithListing, Sort};
+use server::{PagedQuery, PaginatedReponse, Quoted};
+
+use super::{GetNftsQuery, NftSortBy};
+
+pub async fn get_user_nfts(
+    State(AppState {
+        db, price_oracle, ..
+    }): State<AppState>,
+    Path(address): Path<String>,
+    Query(query): Query<GetNftsQuery>,
+    Query(paged_query): Query<PagedQuery>,
+) -> Result<Json<PaginatedReponse<Quoted<NftWithListing>>>, AppError> {
+    let GetNftsQuery {
+        sort_by,
+        sort_direction,
+        traits,
+    } = query;
+
+    let PagedQuery { page, take } = paged_query;
+
+    let (nfts, total) = repositories::nft::find_by_owner(
+        &db,
+        &address,
+        &traits,
+        (Some(page), Some(take)),
+        (
+            sort_by.unwrap_or(NftSortBy::Rarity).to_sort_option(),
+            sort_direction.unwrap_or(Sort::Asc),
+        ),
+    )
+    .await?;
+
+    Ok(Json(PaginatedReponse {
+        page,
+        total,
+        data: nfts
+            .into_iter()
+            .map(|nft| Quoted {
+                quote: nft
+                    .price
+                    .zip(nft.denom.as_deref())
+                    .map(|(price, denom)| price_oracle.quote(price, denom)),
+                data: nft,
+            })
+            .collect(),
+    }))
+}
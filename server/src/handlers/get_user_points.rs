@@ -0,0 +1,25 @@
+use crate::{error::AppError, extractors::AppState};
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use database::{entities::user_loyalty_point, repositories};
+use server::{PagedQuery, PaginatedReponse};
+
+pub async fn get_user_points(
+    State(AppState { db, .. }): State<AppState>,
+    Path(address): Path<String>,
+    Query(paged_query): Query<PagedQuery>,
+) -> Result<Json<PaginatedReponse<user_loyalty_point::Model>>, AppError> {
+    let PagedQuery { page, take } = paged_query;
+
+    let (points, total) =
+        repositories::user_point::find_history_by_wallet(&db, &address, (Some(page), Some(take)))
+            .await?;
+
+    Ok(Json(PaginatedReponse {
+        page,
+        total,
+        data: points,
+    }))
+}
@@ -0,0 +1,134 @@
+use crate::{
+    error::AppError,
+    extractors::{AppState, Guard, ValidatedPayload},
+};
+use axum::{extract::State, Json};
+use database::{
+    entities::{nft, user},
+    repositories::{
+        self,
+        user::{NotificationSettings, UpdateProfileParams},
+        user_point::LoyaltyPointTotals,
+    },
+    DatabaseConnection,
+};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+pub async fn get_me(
+    State(AppState { db, .. }): State<AppState>,
+    Guard(claims): Guard,
+) -> Result<Json<MeResponse>, AppError> {
+    let (user, avatar) = repositories::user::find_with_avatar(&db, &claims.address)
+        .await?
+        .ok_or(user_not_found(&claims.address))?;
+
+    Ok(Json(to_response(&db, user, avatar).await?))
+}
+
+pub async fn update_me(
+    State(AppState { db, .. }): State<AppState>,
+    Guard(claims): Guard,
+    ValidatedPayload(payload): ValidatedPayload<UpdateProfileRequest>,
+) -> Result<Json<MeResponse>, AppError> {
+    let UpdateProfileRequest {
+        display_name,
+        avatar,
+    } = payload;
+
+    let avatar = match avatar {
+        Some(AvatarRequest {
+            collection_address,
+            token_id,
+        }) => {
+            let nft = repositories::nft::find_by_address_and_token_id(
+                &db,
+                &collection_address,
+                &token_id,
+            )
+            .await?
+            .ok_or(AppError::NotFoundError(format!(
+                "nft {} of {} not found",
+                token_id, collection_address
+            )))?;
+
+            if nft.owner_address.as_deref() != Some(&claims.address) {
+                return Err(AppError::BadRequestError(
+                    "Avatar must be an owned nft".into(),
+                ));
+            }
+
+            Some(nft)
+        }
+        None => None,
+    };
+
+    let user = repositories::user::update_profile(
+        &db,
+        &claims.address,
+        UpdateProfileParams {
+            display_name: display_name.filter(|name| !name.trim().is_empty()),
+            avatar_nft_id: avatar.as_ref().map(|nft| nft.id),
+        },
+    )
+    .await?
+    .ok_or(user_not_found(&claims.address))?;
+
+    Ok(Json(to_response(&db, user, avatar).await?))
+}
+
+pub async fn update_me_settings(
+    State(AppState { db, .. }): State<AppState>,
+    Guard(claims): Guard,
+    Json(settings): Json<NotificationSettings>,
+) -> Result<Json<NotificationSettings>, AppError> {
+    let user = repositories::user::update_notification_settings(&db, &claims.address, settings)
+        .await?
+        .ok_or(user_not_found(&claims.address))?;
+
+    Ok(Json(NotificationSettings::from(&user)))
+}
+
+fn user_not_found(address: &str) -> AppError {
+    AppError::NotFoundError(format!("user {} not found", address))
+}
+
+async fn to_response(
+    db: &DatabaseConnection,
+    user: user::Model,
+    avatar: Option<nft::Model>,
+) -> Result<MeResponse, AppError> {
+    let points = repositories::user_point::find_totals_by_wallet(db, &user.address).await?;
+
+    Ok(MeResponse {
+        notifications: NotificationSettings::from(&user),
+        address: user.address,
+        is_new_user: user.is_new_user,
+        display_name: user.display_name,
+        avatar,
+        points,
+    })
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct UpdateProfileRequest {
+    #[validate(length(max = 32))]
+    pub display_name: Option<String>,
+    pub avatar: Option<AvatarRequest>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct AvatarRequest {
+    pub collection_address: String,
+    pub token_id: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct MeResponse {
+    pub address: String,
+    pub is_new_user: bool,
+    pub display_name: Option<String>,
+    pub avatar: Option<nft::Model>,
+    pub notifications: NotificationSettings,
+    pub points: LoyaltyPointTotals,
+}
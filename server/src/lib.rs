@@ -1,4 +1,5 @@
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use service::Quote;
 
 pub fn empty_string_as_none<'r, D>(de: D) -> Result<Option<String>, D::Error>
 where
@@ -11,6 +12,20 @@ where
     Ok(s)
 }
 
+// for structured values that do not fit in a flat query string, e.g. `?traits=[...]`
+pub fn json_string<'r, D, T>(de: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'r>,
+    T: DeserializeOwned + Default,
+{
+    let s = Option::<String>::deserialize(de)?;
+
+    match s.filter(|s| !s.is_empty()) {
+        Some(s) => serde_json::from_str(&s).map_err(serde::de::Error::custom),
+        None => Ok(T::default()),
+    }
+}
+
 #[derive(Deserialize, Debug)]
 pub struct PagedQuery {
     pub page: u32,
@@ -19,7 +34,21 @@ pub struct PagedQuery {
 
 #[derive(Serialize, Debug)]
 pub struct PaginatedReponse<T> {
-    pub total: u32,
+    pub total: u64,
     pub page: u32,
     pub data: Vec<T>,
 }
+
+#[derive(Serialize, Debug)]
+pub struct CursorResponse<T> {
+    pub next_cursor: Option<String>,
+    pub data: Vec<T>,
+}
+
+// a priced row with its price converted to display units, sei and usd
+#[derive(Serialize, Debug)]
+pub struct Quoted<T> {
+    #[serde(flatten)]
+    pub data: T,
+    pub quote: Option<Quote>,
+}
@@ -1,12 +1,23 @@
 #![allow(unused_imports)]
 #![allow(dead_code)]
+mod auth;
+mod cache;
 mod error;
+mod events;
 mod extractors;
 mod handlers;
 
-use axum::{routing::get, Router};
+use axum::{
+    routing::{get, post, put},
+    Router,
+};
 use extractors::AppState;
-use handlers::{get_collections, get_listed_nfts, get_user_nfts};
+use handlers::{
+    get_activities, get_collection_royalties, get_collection_snapshots, get_collection_traits,
+    get_collections, get_leaderboard, get_listed_nfts, get_market_events_sse, get_market_events_ws,
+    get_me, get_nft, get_user_nfts, get_user_points, login, logout, refresh_token, request_nonce,
+    revoke_sessions, update_me, update_me_settings,
+};
 
 #[tokio::main]
 
@@ -17,12 +28,40 @@ async fn main() {
     let redis_url = "redis://127.0.0.1/";
 
     let app = Router::new()
+        .route("/activities", get(get_activities))
+        .route("/auth/login", post(login))
+        .route("/auth/logout", post(logout))
+        .route("/auth/nonce", post(request_nonce))
+        .route("/auth/refresh", post(refresh_token))
+        .route("/auth/revoke", post(revoke_sessions))
         .route("/collections", get(get_collections))
         .route(
             "/collections/:collection_address/nfts",
             get(get_listed_nfts),
         )
+        .route(
+            "/collections/:collection_address/royalties",
+            get(get_collection_royalties),
+        )
+        .route(
+            "/collections/:collection_address/snapshots",
+            get(get_collection_snapshots),
+        )
+        .route(
+            "/collections/:collection_address/nfts/:token_id",
+            get(get_nft),
+        )
+        .route(
+            "/collections/:collection_address/traits",
+            get(get_collection_traits),
+        )
+        .route("/events/sse", get(get_market_events_sse))
+        .route("/events/ws", get(get_market_events_ws))
+        .route("/leaderboard", get(get_leaderboard))
+        .route("/me", get(get_me).put(update_me))
+        .route("/me/settings", put(update_me_settings))
         .route("/users/:address/nfts", get(get_user_nfts))
+        .route("/users/:address/points", get(get_user_points))
         .with_state(AppState::init(&db_url, redis_url).await);
 
     let listener = tokio::net::TcpListener::bind("0.0.0.0:8080").await.unwrap();
@@ -0,0 +1,79 @@
+use redis::{aio::MultiplexedConnection, AsyncCommands, Client, RedisError};
+
+pub type CacheConnection = MultiplexedConnection;
+
+pub async fn connect_cache(redis_url: &str) -> Result<CacheConnection, RedisError> {
+    Client::open(redis_url)?
+        .get_multiplexed_tokio_connection()
+        .await
+}
+
+pub fn nft_cache_key(token_address: &str, token_id: &str) -> String {
+    format!("nft:{}:{}", token_address, token_id)
+}
+
+pub fn collection_traits_cache_key(collection_address: &str) -> String {
+    format!("collection:{}:traits", collection_address)
+}
+
+// the trait catalog counts the listings and floor of the whole collection, it goes with the nft
+pub async fn invalidate_nft(
+    cache: &mut CacheConnection,
+    token_address: &str,
+    token_id: &str,
+) -> Result<(), RedisError> {
+    cache
+        .del::<_, ()>(&[
+            nft_cache_key(token_address, token_id),
+            collection_traits_cache_key(token_address),
+        ])
+        .await
+}
+
+// ranks are recomputed for a whole collection, every cached detail of it is stale
+pub async fn invalidate_collection_nfts(
+    cache: &mut CacheConnection,
+    collection_address: &str,
+) -> Result<usize, RedisError> {
+    let mut keys = vec![];
+    let mut iter = cache
+        .scan_match::<_, String>(nft_cache_key(collection_address, "*"))
+        .await?;
+
+    while let Some(key) = iter.next_item().await {
+        keys.push(key);
+    }
+
+    drop(iter);
+
+    if keys.is_empty() {
+        return Ok(0);
+    }
+
+    cache.del::<_, ()>(&keys).await?;
+
+    Ok(keys.len())
+}
+
+// collections whose traits changed since their rarity was last computed
+static STALE_RARITY_KEY: &str = "rarity:stale";
+
+pub async fn mark_rarity_stale(
+    cache: &mut CacheConnection,
+    collection_address: &str,
+) -> Result<(), RedisError> {
+    cache
+        .sadd::<_, _, ()>(STALE_RARITY_KEY, collection_address)
+        .await
+}
+
+// removed before the recompute so a collection marked meanwhile is kept for the next run
+pub async fn take_stale_rarity(cache: &mut CacheConnection) -> Result<Vec<String>, RedisError> {
+    let addresses = cache.smembers::<_, Vec<String>>(STALE_RARITY_KEY).await?;
+
+    if !addresses.is_empty() {
+        cache.srem::<_, _, ()>(STALE_RARITY_KEY, &addresses).await?;
+    }
+
+    Ok(addresses)
+}
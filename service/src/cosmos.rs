@@ -1,14 +1,17 @@
 use prost::{DecodeError, Message};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::json;
-use tendermint::{hash::Algorithm, Hash};
+use tendermint::{block::Height, hash::Algorithm, Hash, Time};
 use tendermint_rpc::{
-    endpoint::{header_by_hash, tx, tx_search},
+    endpoint::{block, block_results, header, tx, tx_search},
     query::Query,
     Client, HttpClient, Order,
 };
 
-use crate::PALLET_CONTRACT_ADDRESS;
+use crate::{
+    decode_abi_string, decode_abi_uint, encode_call, NAME_SELECTOR, PALLET_CONTRACT_ADDRESS,
+    SYMBOL_SELECTOR, TOKEN_URI_SELECTOR, TOTAL_SUPPLY_SELECTOR,
+};
 
 pub struct CosmosClient(HttpClient);
 
@@ -28,6 +31,9 @@ pub enum CosmosClientError {
 
     #[error("Hash Error")]
     TendermintHashError(#[from] tendermint::error::Error),
+
+    #[error("Abi Error : {0}")]
+    AbiError(String),
 }
 
 impl CosmosClient {
@@ -57,6 +63,42 @@ impl CosmosClient {
         self.query_contract(address, msg).await
     }
 
+    // cw2981 contracts answer royalty queries through the cw721 extension entry point
+    pub async fn check_cw2981_royalties(
+        &self,
+        address: &str,
+    ) -> Result<CheckRoyaltiesResponse, CosmosClientError> {
+        let msg = json!({
+            "extension": {
+                "msg": {
+                    "check_royalties": {}
+                }
+            }
+        });
+
+        self.query_contract(address, msg).await
+    }
+
+    pub async fn get_cw2981_royalty_info(
+        &self,
+        address: &str,
+        token_id: &str,
+        sale_price: u128,
+    ) -> Result<RoyaltiesInfoResponse, CosmosClientError> {
+        let msg = json!({
+            "extension": {
+                "msg": {
+                    "royalty_info": {
+                        "token_id": token_id,
+                        "sale_price": sale_price.to_string()
+                    }
+                }
+            }
+        });
+
+        self.query_contract(address, msg).await
+    }
+
     pub fn as_http(&self) -> &HttpClient {
         &self.0
     }
@@ -105,22 +147,50 @@ impl CosmosClient {
     }
 
     pub async fn get_tx(&self, tx_hash: &str) -> Result<tx::Response, CosmosClientError> {
-        let tx_hash = Hash::from_bytes(Algorithm::Sha256, tx_hash.as_bytes())?;
-        let tx = self.as_http().tx(tx_hash.to_owned(), false).await?;
+        let tx_hash = parse_tx_hash(tx_hash)?;
+        let tx = self.as_http().tx(tx_hash, false).await?;
 
         Ok(tx)
     }
 
+    // header of the block the tx was included in
     pub async fn get_tx_header(
         &self,
         tx_hash: &str,
-    ) -> Result<header_by_hash::Response, CosmosClientError> {
-        let tx_hash = Hash::from_bytes(Algorithm::Sha256, tx_hash.as_bytes())?;
-        let header = self.as_http().header_by_hash(tx_hash).await?;
+    ) -> Result<header::Response, CosmosClientError> {
+        let tx = self.get_tx(tx_hash).await?;
+        let header = self.as_http().header(tx.height).await?;
 
         Ok(header)
     }
 
+    pub async fn get_block(&self, height: u64) -> Result<block::Response, CosmosClientError> {
+        let block = self.as_http().block(to_height(height)?).await?;
+
+        Ok(block)
+    }
+
+    pub async fn get_block_results(
+        &self,
+        height: u64,
+    ) -> Result<block_results::Response, CosmosClientError> {
+        let results = self.as_http().block_results(to_height(height)?).await?;
+
+        Ok(results)
+    }
+
+    pub async fn get_latest_height(&self) -> Result<u64, CosmosClientError> {
+        let status = self.as_http().status().await?;
+
+        Ok(status.sync_info.latest_block_height.value())
+    }
+
+    pub async fn get_block_time(&self, height: u64) -> Result<Time, CosmosClientError> {
+        let header = self.as_http().header(to_height(height)?).await?;
+
+        Ok(header.header.time)
+    }
+
     pub async fn search_tx(
         &self,
         query: Query,
@@ -135,6 +205,111 @@ impl CosmosClient {
         Ok(res)
     }
 
+    // the cw721 counterpart of an evm contract, `pointer_type` is the kind of the pointer contract
+    pub async fn get_evm_pointer(
+        &self,
+        pointer_type: PointerType,
+        pointee: &str,
+    ) -> Result<PointerResponse, CosmosClientError> {
+        let query = QueryPointerRequest {
+            pointer_type: pointer_type as i32,
+            pointee: pointee.to_string(),
+        };
+
+        self.query_grpc("/seiprotocol.seichain.evm.Query/Pointer", query)
+            .await
+    }
+
+    pub async fn get_evm_pointee(
+        &self,
+        pointer_type: PointerType,
+        pointer: &str,
+    ) -> Result<PointeeResponse, CosmosClientError> {
+        let query = QueryPointeeRequest {
+            pointer_type: pointer_type as i32,
+            pointer: pointer.to_string(),
+        };
+
+        self.query_grpc("/seiprotocol.seichain.evm.Query/Pointee", query)
+            .await
+    }
+
+    // an evm address only has a sei address once its owner linked both
+    pub async fn get_sei_address(
+        &self,
+        evm_address: &str,
+    ) -> Result<SeiAddressResponse, CosmosClientError> {
+        let query = QuerySeiAddressByEvmAddressRequest {
+            evm_address: evm_address.to_string(),
+        };
+
+        self.query_grpc(
+            "/seiprotocol.seichain.evm.Query/SeiAddressByEVMAddress",
+            query,
+        )
+        .await
+    }
+
+    // native erc721s have no cw721 interface, they answer the erc721 metadata extension
+    pub async fn get_erc721_contract_info(
+        &self,
+        address: &str,
+    ) -> Result<ContractInfo, CosmosClientError> {
+        let name = self
+            .evm_static_call(address, NAME_SELECTOR.to_vec())
+            .await?;
+        let symbol = self
+            .evm_static_call(address, SYMBOL_SELECTOR.to_vec())
+            .await?;
+
+        Ok(ContractInfo {
+            name: decode_abi_string(&name).ok_or(abi_error("name", address))?,
+            symbol: decode_abi_string(&symbol).ok_or(abi_error("symbol", address))?,
+        })
+    }
+
+    // only enumerable erc721s know their supply
+    pub async fn get_erc721_contract_supply(
+        &self,
+        address: &str,
+    ) -> Result<Supply, CosmosClientError> {
+        let data = self
+            .evm_static_call(address, TOTAL_SUPPLY_SELECTOR.to_vec())
+            .await?;
+
+        let count = decode_abi_uint(&data)
+            .and_then(|count| count.parse::<u32>().ok())
+            .ok_or(abi_error("totalSupply", address))?;
+
+        Ok(Supply { count })
+    }
+
+    pub async fn get_erc721_token_uri(
+        &self,
+        address: &str,
+        token_id: &str,
+    ) -> Result<String, CosmosClientError> {
+        let call =
+            encode_call(TOKEN_URI_SELECTOR, &[token_id]).ok_or(abi_error("tokenURI", address))?;
+
+        let data = self.evm_static_call(address, call).await?;
+
+        decode_abi_string(&data).ok_or(abi_error("tokenURI", address))
+    }
+
+    async fn evm_static_call(&self, to: &str, data: Vec<u8>) -> Result<Vec<u8>, CosmosClientError> {
+        let query = QueryStaticCallRequest {
+            data,
+            to: to.to_string(),
+        };
+
+        let res: QueryStaticCallResponse = self
+            .query_grpc("/seiprotocol.seichain.evm.Query/StaticCall", query)
+            .await?;
+
+        Ok(res.data)
+    }
+
     async fn query_contract<T, U>(&self, address: &str, msg: T) -> Result<U, CosmosClientError>
     where
         T: Serialize,
@@ -145,26 +320,48 @@ impl CosmosClient {
             query_data: serde_json::to_vec(&msg)?,
         };
 
+        let raw = self
+            .query_grpc::<_, QueryRawContractResponse>(
+                "/cosmwasm.wasm.v1.Query/SmartContractState",
+                query,
+            )
+            .await?;
+
+        let res = serde_json::from_slice::<U>(raw.data.as_slice())?;
+
+        Ok(res)
+    }
+
+    async fn query_grpc<T, U>(&self, path: &str, query: T) -> Result<U, CosmosClientError>
+    where
+        T: Message,
+        U: Message + Default,
+    {
         let res = self
             .as_http()
-            .abci_query(
-                Some("/cosmwasm.wasm.v1.Query/SmartContractState".to_string()),
-                query.encode_to_vec(),
-                None,
-                false,
-            )
+            .abci_query(Some(path.to_string()), query.encode_to_vec(), None, false)
             .await?;
 
         if res.code.is_err() {
             return Err(CosmosClientError::RpcError(res.log));
         }
 
-        let raw = QueryRawContractResponse::decode(res.value.as_slice())?;
+        Ok(U::decode(res.value.as_slice())?)
+    }
+}
+
+// tx hashes are hex, from events they may come lowercase or with a 0x prefix
+pub fn parse_tx_hash(tx_hash: &str) -> Result<Hash, CosmosClientError> {
+    let tx_hash = tx_hash.strip_prefix("0x").unwrap_or(tx_hash).to_uppercase();
+    let hash = Hash::from_hex_upper(Algorithm::Sha256, &tx_hash)?;
 
-        let res = serde_json::from_slice::<U>(raw.data.as_slice())?;
+    Ok(hash)
+}
 
-        Ok(res)
-    }
+fn to_height(height: u64) -> Result<Height, CosmosClientError> {
+    let height = Height::try_from(height)?;
+
+    Ok(height)
 }
 
 #[derive(Deserialize, Debug)]
@@ -189,6 +386,17 @@ pub struct Extension {
     pub royalty_percentage: Option<f32>,
 }
 
+#[derive(Deserialize, Debug)]
+pub struct CheckRoyaltiesResponse {
+    pub royalty_payments: bool,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RoyaltiesInfoResponse {
+    pub address: String,
+    pub royalty_amount: String,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct NftOwner {
     pub owner: String,
@@ -216,6 +424,87 @@ pub struct Price {
     pub denom: String,
 }
 
+#[derive(Clone, Copy, Debug)]
+pub enum PointerType {
+    Erc20 = 0,
+    Erc721 = 1,
+    Native = 2,
+    Cw20 = 3,
+    Cw721 = 4,
+}
+
+#[derive(prost::Message)]
+pub struct PointerResponse {
+    #[prost(string, tag = "1")]
+    pub pointer: prost::alloc::string::String,
+
+    #[prost(uint32, tag = "2")]
+    pub version: u32,
+
+    #[prost(bool, tag = "3")]
+    pub exists: bool,
+}
+
+#[derive(prost::Message)]
+pub struct PointeeResponse {
+    #[prost(string, tag = "1")]
+    pub pointee: prost::alloc::string::String,
+
+    #[prost(uint32, tag = "2")]
+    pub version: u32,
+
+    #[prost(bool, tag = "3")]
+    pub exists: bool,
+}
+
+#[derive(prost::Message)]
+pub struct SeiAddressResponse {
+    #[prost(string, tag = "1")]
+    pub sei_address: prost::alloc::string::String,
+
+    #[prost(bool, tag = "2")]
+    pub associated: bool,
+}
+
+#[derive(prost::Message)]
+struct QueryPointerRequest {
+    #[prost(int32, tag = "1")]
+    pointer_type: i32,
+
+    #[prost(string, tag = "2")]
+    pointee: prost::alloc::string::String,
+}
+
+#[derive(prost::Message)]
+struct QueryPointeeRequest {
+    #[prost(int32, tag = "1")]
+    pointer_type: i32,
+
+    #[prost(string, tag = "2")]
+    pointer: prost::alloc::string::String,
+}
+
+#[derive(prost::Message)]
+struct QuerySeiAddressByEvmAddressRequest {
+    #[prost(string, tag = "1")]
+    evm_address: prost::alloc::string::String,
+}
+
+#[derive(prost::Message)]
+struct QueryStaticCallRequest {
+    #[prost(bytes = "vec", tag = "1")]
+    data: prost::alloc::vec::Vec<u8>,
+
+    #[prost(string, tag = "2")]
+    to: prost::alloc::string::String,
+}
+
+#[derive(prost::Message)]
+struct QueryStaticCallResponse {
+    #[prost(bytes = "vec", tag = "1")]
+    data: prost::alloc::vec::Vec<u8>,
+}
+
 #[derive(prost::Message)]
 struct QueryContractRequest {
     #[prost(string, tag = "1")]
@@ -230,3 +519,7 @@ struct QueryRawContractResponse {
     #[prost(bytes = "vec", tag = "1")]
     pub data: prost::alloc::vec::Vec<u8>,
 }
+
+fn abi_error(function: &str, address: &str) -> CosmosClientError {
+    CosmosClientError::AbiError(format!("can not decode {} of {}", function, address))
+}
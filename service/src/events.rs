@@ -0,0 +1,54 @@
+use redis::{AsyncCommands, RedisError};
+use serde::{Deserialize, Serialize};
+
+use crate::CacheConnection;
+
+pub static MARKET_EVENT_CHANNEL: &str = "market_events";
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum MarketEventKind {
+    Sale,
+    List,
+    Delist,
+    Offer,
+    Mint,
+    Transfer,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Marketplace {
+    Evm,
+    Mrkt,
+    Pallet,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MarketEvent {
+    pub kind: MarketEventKind,
+    pub collection_address: String,
+    pub token_id: String,
+    pub tx_hash: String,
+    pub marketplace: Option<Marketplace>,
+    pub seller: Option<String>,
+    pub buyer: Option<String>,
+    pub price: Option<String>,
+    pub denom: Option<String>,
+    pub date: i64,
+}
+
+impl MarketEvent {
+    pub fn involves(&self, wallet: &str) -> bool {
+        self.seller.as_deref() == Some(wallet) || self.buyer.as_deref() == Some(wallet)
+    }
+}
+
+pub async fn publish_market_event(
+    cache: &mut CacheConnection,
+    event: &MarketEvent,
+) -> Result<(), RedisError> {
+    cache
+        .publish::<_, _, ()>(MARKET_EVENT_CHANNEL, serde_json::json!(event).to_string())
+        .await
+}
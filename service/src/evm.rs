@@ -0,0 +1,353 @@
+use prost::{DecodeError, Message};
+use serde::Serialize;
+
+// keccak256 of `Transfer(address,address,uint256)`, erc721 indexes the token id so it has 4 topics
+pub static TRANSFER_TOPIC: &str =
+    "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+
+pub static ZERO_ADDRESS: &str = "0x0000000000000000000000000000000000000000";
+
+// keccak256 of seaport's
+// `OrderFulfilled(bytes32,address,address,address,(uint8,address,uint256,uint256)[],(uint8,address,uint256,uint256,address)[])`
+pub static ORDER_FULFILLED_TOPIC: &str =
+    "0x9d9af8e38d66c62e2c12f0225249fd9d721c54b83f48d9352c97c6cacdcb6f31";
+
+// selectors of the erc721 metadata and enumerable extensions
+pub static NAME_SELECTOR: [u8; 4] = [0x06, 0xfd, 0xde, 0x03];
+pub static SYMBOL_SELECTOR: [u8; 4] = [0x95, 0xd8, 0x9b, 0x41];
+pub static TOKEN_URI_SELECTOR: [u8; 4] = [0xc8, 0x7b, 0x56, 0xdd];
+pub static TOTAL_SUPPLY_SELECTOR: [u8; 4] = [0x18, 0x16, 0x0d, 0xdd];
+
+// older sdk versions tag the response with the request type url
+static EVM_TRANSACTION_TYPE_URL: &str = "/seiprotocol.seichain.evm.MsgEVMTransaction";
+
+#[derive(Debug)]
+pub struct EvmLog {
+    pub address: String,
+    pub topics: Vec<String>,
+    pub data: Vec<u8>,
+    pub index: u32,
+    // mirrors a cosmwasm event of a pointer contract, the wasm event is the source of truth
+    pub synthetic: bool,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Erc721Transfer {
+    pub contract: String,
+    pub from: String,
+    pub to: String,
+    pub token_id: String,
+}
+
+// criteria items are reported with the identifier they resolved to
+#[derive(Debug, PartialEq)]
+pub enum ItemType {
+    Native,
+    Erc20,
+    Erc721,
+    Erc1155,
+}
+
+#[derive(Debug)]
+pub struct OrderItem {
+    pub item_type: ItemType,
+    pub token: String,
+    pub identifier: String,
+    pub amount: String,
+    // only consideration items have a recipient
+    pub recipient: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct OrderFulfilled {
+    pub offerer: String,
+    pub recipient: String,
+    pub offer: Vec<OrderItem>,
+    pub consideration: Vec<OrderItem>,
+}
+
+impl OrderItem {
+    pub fn is_nft(&self, contract: &str, token_id: &str) -> bool {
+        self.item_type == ItemType::Erc721
+            && self.token.eq_ignore_ascii_case(contract)
+            && self.identifier == token_id
+    }
+
+    pub fn is_payment(&self) -> bool {
+        matches!(self.item_type, ItemType::Native | ItemType::Erc20)
+    }
+}
+
+impl OrderFulfilled {
+    pub fn trades(&self, contract: &str, token_id: &str) -> bool {
+        self.offer
+            .iter()
+            .chain(self.consideration.iter())
+            .any(|item| item.is_nft(contract, token_id))
+    }
+}
+
+impl EvmLog {
+    pub fn to_erc721_transfer(&self) -> Option<Erc721Transfer> {
+        let [topic, from, to, token_id] = self.topics.as_slice() else {
+            return None;
+        };
+
+        if !topic.eq_ignore_ascii_case(TRANSFER_TOPIC) {
+            return None;
+        }
+
+        Some(Erc721Transfer {
+            contract: self.address.to_lowercase(),
+            from: topic_to_address(from)?,
+            to: topic_to_address(to)?,
+            token_id: topic_to_decimal(token_id)?,
+        })
+    }
+
+    pub fn to_order_fulfilled(&self) -> Option<OrderFulfilled> {
+        let [topic, offerer, _zone] = self.topics.as_slice() else {
+            return None;
+        };
+
+        if !topic.eq_ignore_ascii_case(ORDER_FULFILLED_TOPIC) {
+            return None;
+        }
+
+        // order hash, recipient, then the offsets of both arrays
+        let offer = word_to_usize(word(&self.data, 2)?)?;
+        let consideration = word_to_usize(word(&self.data, 3)?)?;
+
+        Some(OrderFulfilled {
+            offerer: topic_to_address(offerer)?,
+            recipient: word_to_address(word(&self.data, 1)?),
+            offer: decode_order_items(self.data.get(offer..)?, false)?,
+            consideration: decode_order_items(self.data.get(consideration..)?, true)?,
+        })
+    }
+}
+
+// `data` is the result data of a tendermint tx, only evm messages carry logs
+pub fn decode_evm_logs(data: &[u8]) -> Result<Vec<EvmLog>, DecodeError> {
+    let TxMsgData {
+        data,
+        msg_responses,
+    } = TxMsgData::decode(data)?;
+
+    let mut logs = Vec::new();
+
+    for response in data.iter().chain(msg_responses.iter()) {
+        if !response.type_url.starts_with(EVM_TRANSACTION_TYPE_URL) {
+            continue;
+        }
+
+        let response = MsgEvmTransactionResponse::decode(response.value.as_slice())?;
+
+        logs.extend(response.logs.into_iter().map(|log| EvmLog {
+            address: log.address.to_lowercase(),
+            topics: log.topics,
+            data: log.data,
+            index: log.index,
+            synthetic: log.synthetic,
+        }));
+    }
+
+    Ok(logs)
+}
+
+fn topic_to_address(topic: &str) -> Option<String> {
+    let hex = topic.strip_prefix("0x").unwrap_or(topic);
+
+    (hex.len() == 64).then(|| format!("0x{}", hex[24..].to_lowercase()))
+}
+
+// token ids are uint256, too large for any native integer
+pub fn topic_to_decimal(topic: &str) -> Option<String> {
+    let hex = topic.strip_prefix("0x").unwrap_or(topic);
+
+    // little endian base 10 digits
+    let mut digits = vec![0u32];
+
+    for c in hex.chars() {
+        let mut carry = c.to_digit(16)?;
+
+        for digit in digits.iter_mut() {
+            let value = *digit * 16 + carry;
+            *digit = value % 10;
+            carry = value / 10;
+        }
+
+        while carry > 0 {
+            digits.push(carry % 10);
+            carry /= 10;
+        }
+    }
+
+    while digits.len() > 1 && digits.last() == Some(&0) {
+        digits.pop();
+    }
+
+    Some(
+        digits
+            .iter()
+            .rev()
+            .filter_map(|digit| char::from_digit(*digit, 10))
+            .collect(),
+    )
+}
+
+// abi encoded calldata of a function taking only uint256 arguments, given as decimals
+pub fn encode_call(selector: [u8; 4], args: &[&str]) -> Option<Vec<u8>> {
+    let mut data = selector.to_vec();
+
+    for arg in args {
+        data.extend(decimal_to_word(arg)?);
+    }
+
+    Some(data)
+}
+
+pub fn decode_abi_string(data: &[u8]) -> Option<String> {
+    let data = data.get(word_to_usize(word(data, 0)?)?..)?;
+    let len = word_to_usize(word(data, 0)?)?;
+
+    // the length comes from the contract, a huge one must not overflow the range
+    String::from_utf8(data.get(32..32usize.checked_add(len)?)?.to_vec()).ok()
+}
+
+pub fn decode_abi_uint(data: &[u8]) -> Option<String> {
+    word_to_decimal(word(data, 0)?)
+}
+
+fn decode_order_items(data: &[u8], with_recipient: bool) -> Option<Vec<OrderItem>> {
+    let len = word_to_usize(word(data, 0)?)?;
+    let size = if with_recipient { 5 } else { 4 };
+
+    (0..len)
+        .map(|index| {
+            let base = 1 + index * size;
+
+            Some(OrderItem {
+                item_type: match word_to_usize(word(data, base)?)? {
+                    0 => ItemType::Native,
+                    1 => ItemType::Erc20,
+                    2 | 4 => ItemType::Erc721,
+                    3 | 5 => ItemType::Erc1155,
+                    _ => return None,
+                },
+                token: word_to_address(word(data, base + 1)?),
+                identifier: word_to_decimal(word(data, base + 2)?)?,
+                amount: word_to_decimal(word(data, base + 3)?)?,
+                recipient: match with_recipient {
+                    true => Some(word_to_address(word(data, base + 4)?)),
+                    false => None,
+                },
+            })
+        })
+        .collect()
+}
+
+fn word(data: &[u8], index: usize) -> Option<&[u8]> {
+    data.get(index * 32..(index + 1) * 32)
+}
+
+fn word_to_hex(word: &[u8]) -> String {
+    word.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn word_to_address(word: &[u8]) -> String {
+    format!("0x{}", word_to_hex(&word[12..]))
+}
+
+fn word_to_decimal(word: &[u8]) -> Option<String> {
+    topic_to_decimal(&word_to_hex(word))
+}
+
+// offsets and lengths, anything past u64 can not index the data anyway
+fn word_to_usize(word: &[u8]) -> Option<usize> {
+    if word[..24].iter().any(|byte| *byte != 0) {
+        return None;
+    }
+
+    usize::try_from(u64::from_be_bytes(word[24..].try_into().ok()?)).ok()
+}
+
+fn decimal_to_word(decimal: &str) -> Option<[u8; 32]> {
+    // big endian base 256 digits
+    let mut word = [0u8; 32];
+
+    if decimal.is_empty() {
+        return None;
+    }
+
+    for c in decimal.chars() {
+        let mut carry = c.to_digit(10)?;
+
+        for byte in word.iter_mut().rev() {
+            let value = *byte as u32 * 10 + carry;
+            *byte = (value % 256) as u8;
+            carry = value / 256;
+        }
+
+        if carry > 0 {
+            return None;
+        }
+    }
+
+    Some(word)
+}
+
+// `MsgData` of older sdk versions and `Any` share the same layout
+#[derive(prost::Message)]
+struct TypedData {
+    #[prost(string, tag = "1")]
+    type_url: prost::alloc::string::String,
+
+    #[prost(bytes = "vec", tag = "2")]
+    value: prost::alloc::vec::Vec<u8>,
+}
+
+#[derive(prost::Message)]
+struct TxMsgData {
+    #[prost(message, repeated, tag = "1")]
+    data: prost::alloc::vec::Vec<TypedData>,
+
+    #[prost(message, repeated, tag = "2")]
+    msg_responses: prost::alloc::vec::Vec<TypedData>,
+}
+
+#[derive(prost::Message)]
+struct MsgEvmTransactionResponse {
+    #[prost(uint64, tag = "1")]
+    gas_used: u64,
+
+    #[prost(string, tag = "2")]
+    vm_error: prost::alloc::string::String,
+
+    #[prost(bytes = "vec", tag = "3")]
+    return_data: prost::alloc::vec::Vec<u8>,
+
+    #[prost(string, tag = "4")]
+    hash: prost::alloc::string::String,
+
+    #[prost(message, repeated, tag = "5")]
+    logs: prost::alloc::vec::Vec<Log>,
+}
+
+#[derive(prost::Message)]
+struct Log {
+    #[prost(string, tag = "1")]
+    address: prost::alloc::string::String,
+
+    #[prost(string, repeated, tag = "2")]
+    topics: prost::alloc::vec::Vec<prost::alloc::string::String>,
+
+    #[prost(bytes = "vec", tag = "3")]
+    data: prost::alloc::vec::Vec<u8>,
+
+    #[prost(uint32, tag = "4")]
+    index: u32,
+
+    #[prost(bool, tag = "5")]
+    synthetic: bool,
+}
@@ -1,13 +1,25 @@
+mod cache;
 mod cosmos;
+mod events;
+mod evm;
 mod http;
-static PALLET_API_URL: &'static str = "https://api.pallet.exchange/api";
+mod price;
+mod signature;
+mod tx;
+static PALLET_API_URL: &str = "https://api.pallet.exchange/api";
 
-pub static PALLET_CONTRACT_ADDRESS: &'static str =
+pub static PALLET_CONTRACT_ADDRESS: &str =
     "sei152u2u0lqc27428cuf8dx48k8saua74m6nql5kgvsu4rfeqm547rsnhy4y9";
 
-pub static MRKT_CONTRACT_ADDRESS: &'static str =
+pub static MRKT_CONTRACT_ADDRESS: &str =
     "sei1dkp90y3jpp2dres2ssp5rak2k6mc7l4nsxz58nktxjsxqp88fcasmrr672";
 
 pub type ServiceError = reqwest::Error;
+pub use cache::*;
 pub use cosmos::*;
+pub use events::*;
+pub use evm::*;
 pub use http::*;
+pub use price::*;
+pub use signature::*;
+pub use tx::*;
@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+pub static SEI_DENOM: &str = "usei";
+
+// most cosmos tokens, ibc ones included, use micro units
+static DEFAULT_DECIMALS: u32 = 6;
+
+// the largest scale a decimal can hold
+static MAX_SCALE: u32 = 28;
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct DenomPrice {
+    pub denom: String,
+    pub symbol: String,
+    pub decimals: u32,
+    // price of one display unit
+    pub usd: Option<Decimal>,
+}
+
+// an amount in display units with its value in sei and usd when the oracle knows them
+#[derive(Serialize, Debug)]
+pub struct Quote {
+    pub amount: Decimal,
+    pub symbol: String,
+    pub sei: Option<Decimal>,
+    pub usd: Option<Decimal>,
+}
+
+// lookups are sync so implementations refreshing from a remote source keep their own cache
+pub trait PriceOracle: Send + Sync {
+    fn find(&self, denom: &str) -> Option<DenomPrice>;
+
+    // `amount` is in the smallest unit of `denom`, as stored by the indexers
+    fn quote(&self, amount: Decimal, denom: &str) -> Quote {
+        let price = self.find(denom).unwrap_or_else(|| unknown_denom(denom));
+
+        let amount = to_display_amount(amount, price.decimals);
+        let usd = price.usd.map(|usd| (amount * usd).round_dp(2));
+
+        let sei = if denom == SEI_DENOM {
+            Some(amount)
+        } else {
+            usd.zip(self.find(SEI_DENOM).and_then(|sei| sei.usd))
+                .filter(|(_, sei_usd)| !sei_usd.is_zero())
+                .map(|(usd, sei_usd)| (usd / sei_usd).round_dp(6))
+        };
+
+        Quote {
+            amount,
+            symbol: price.symbol,
+            sei,
+            usd,
+        }
+    }
+}
+
+pub struct StaticPriceOracle(HashMap<String, DenomPrice>);
+
+impl StaticPriceOracle {
+    pub fn new(prices: impl IntoIterator<Item = DenomPrice>) -> Self {
+        Self(
+            prices
+                .into_iter()
+                .map(|price| (price.denom.to_owned(), price))
+                .collect(),
+        )
+    }
+
+    // a json list of `DenomPrice`, prices stay as written until the file is loaded again
+    pub fn from_file(path: &str) -> Result<Self, PriceOracleError> {
+        let content = std::fs::read_to_string(path)?;
+        let prices = serde_json::from_str::<Vec<DenomPrice>>(&content)?;
+
+        Ok(Self::new(prices))
+    }
+}
+
+impl Default for StaticPriceOracle {
+    fn default() -> Self {
+        Self::new([DenomPrice {
+            denom: SEI_DENOM.to_owned(),
+            symbol: "SEI".to_owned(),
+            decimals: DEFAULT_DECIMALS,
+            usd: None,
+        }])
+    }
+}
+
+impl PriceOracle for StaticPriceOracle {
+    fn find(&self, denom: &str) -> Option<DenomPrice> {
+        self.0.get(denom).cloned()
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum PriceOracleError {
+    #[error("Io Error")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Json Error")]
+    JsonError(#[from] serde_json::Error),
+}
+
+pub fn to_display_amount(amount: Decimal, decimals: u32) -> Decimal {
+    // larger decimals are applied in steps
+    let mut amount = amount;
+    let mut decimals = decimals;
+
+    while decimals > 0 {
+        let scale = decimals.min(MAX_SCALE);
+        amount *= Decimal::from_i128_with_scale(1, scale);
+        decimals -= scale;
+    }
+
+    amount.normalize()
+}
+
+// `ibc/<hash>` and `factory/<creator>/<subdenom>` are shortened, native denoms drop their `u` prefix
+pub fn unknown_denom(denom: &str) -> DenomPrice {
+    let symbol = match denom.split('/').collect::<Vec<_>>().as_slice() {
+        ["ibc", hash] => format!("IBC/{}", &hash[..hash.len().min(6)]),
+        ["factory", _, subdenom] => subdenom.to_uppercase(),
+        _ => denom.strip_prefix('u').unwrap_or(denom).to_uppercase(),
+    };
+
+    DenomPrice {
+        denom: denom.to_owned(),
+        symbol,
+        decimals: DEFAULT_DECIMALS,
+        usd: None,
+    }
+}
@@ -0,0 +1,54 @@
+use base64::{prelude::BASE64_STANDARD, Engine};
+use k256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
+
+use crate::pub_key_to_address;
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum SignatureError {
+    #[error("Invalid public key")]
+    PubKey,
+    #[error("Public key does not belong to address")]
+    Signer,
+    #[error("Invalid signature")]
+    Signature,
+}
+
+// wallets sign arbitrary data (ADR-036) as an amino `sign/MsgSignData` doc with empty
+// chain/account/fee fields, `pub_key` is a compressed secp256k1 key
+pub fn verify_arbitrary_signature(
+    signer: &str,
+    data: &[u8],
+    pub_key: &[u8],
+    signature: &[u8],
+) -> Result<(), SignatureError> {
+    let verifying_key =
+        VerifyingKey::from_sec1_bytes(pub_key).map_err(|_| SignatureError::PubKey)?;
+    let pub_key = verifying_key.to_encoded_point(true);
+
+    if pub_key_to_address(pub_key.as_bytes()).as_deref() != Some(signer) {
+        return Err(SignatureError::Signer);
+    }
+
+    let signature = Signature::from_slice(signature).map_err(|_| SignatureError::Signature)?;
+    let signature = signature.normalize_s().unwrap_or(signature);
+
+    // keys are already sorted, amino json is the compact form of this document
+    let sign_doc = serde_json::json!({
+        "account_number": "0",
+        "chain_id": "",
+        "fee": { "amount": [], "gas": "0" },
+        "memo": "",
+        "msgs": [{
+            "type": "sign/MsgSignData",
+            "value": {
+                "data": BASE64_STANDARD.encode(data),
+                "signer": signer,
+            },
+        }],
+        "sequence": "0",
+    });
+
+    verifying_key
+        .verify(sign_doc.to_string().as_bytes(), &signature)
+        .map_err(|_| SignatureError::Signature)
+}
@@ -0,0 +1,197 @@
+use bech32::{Bech32, Hrp};
+use prost::{DecodeError, Message};
+use ripemd::Ripemd160;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+static ADDRESS_PREFIX: &str = "sei";
+static SECP256K1_PUB_KEY_TYPE_URL: &str = "/cosmos.crypto.secp256k1.PubKey";
+static EXECUTE_CONTRACT_TYPE_URL: &str = "/cosmwasm.wasm.v1.MsgExecuteContract";
+
+#[derive(Serialize, Debug)]
+pub struct DecodedTx {
+    pub signer: Option<String>,
+    pub fee: Fee,
+    pub memo: String,
+    // other messages are not needed by the indexers
+    pub messages: Vec<ExecuteContractMsg>,
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct Fee {
+    pub amount: Vec<Coin>,
+    pub gas_limit: u64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Coin {
+    pub denom: String,
+    pub amount: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ExecuteContractMsg {
+    pub sender: String,
+    pub contract: String,
+    pub msg: serde_json::Value,
+    pub funds: Vec<Coin>,
+}
+
+impl ExecuteContractMsg {
+    pub fn funds_of(&self, denom: &str) -> Option<&str> {
+        self.funds
+            .iter()
+            .find(|coin| coin.denom == denom)
+            .map(|coin| coin.amount.as_str())
+    }
+
+    // marketplace msgs name their nft as `{ "<action>": { "nft": { "address", "token_id" } } }`
+    pub fn names_nft(&self, action: &str, token_address: &str, token_id: &str) -> bool {
+        let nft = &self.msg[action]["nft"];
+
+        nft["address"] == token_address && nft["token_id"] == token_id
+    }
+}
+
+// `raw` are the `TxRaw` bytes of a tendermint tx
+pub fn decode_tx(raw: &[u8]) -> Result<DecodedTx, DecodeError> {
+    let TxRaw {
+        body_bytes,
+        auth_info_bytes,
+    } = TxRaw::decode(raw)?;
+
+    let TxBody { messages, memo } = TxBody::decode(body_bytes.as_slice())?;
+    let AuthInfo { signer_infos, fee } = AuthInfo::decode(auth_info_bytes.as_slice())?;
+
+    let messages = messages
+        .into_iter()
+        .filter(|message| message.type_url == EXECUTE_CONTRACT_TYPE_URL)
+        .map(|message| {
+            let msg = MsgExecuteContract::decode(message.value.as_slice())?;
+
+            Ok(ExecuteContractMsg {
+                sender: msg.sender,
+                contract: msg.contract,
+                msg: serde_json::from_slice(&msg.msg).unwrap_or_default(),
+                funds: msg.funds.into_iter().map(Coin::from).collect(),
+            })
+        })
+        .collect::<Result<Vec<_>, DecodeError>>()?;
+
+    // only secp256k1 keys map to a sei address this way, evm keys fall back to the sender
+    let signer = signer_infos
+        .first()
+        .and_then(|info| info.public_key.as_ref())
+        .filter(|key| key.type_url == SECP256K1_PUB_KEY_TYPE_URL)
+        .and_then(|key| PubKey::decode(key.value.as_slice()).ok())
+        .and_then(|key| pub_key_to_address(&key.key))
+        .or_else(|| messages.first().map(|message| message.sender.to_owned()));
+
+    Ok(DecodedTx {
+        signer,
+        fee: fee
+            .map(|fee| Fee {
+                amount: fee.amount.into_iter().map(Coin::from).collect(),
+                gas_limit: fee.gas_limit,
+            })
+            .unwrap_or_default(),
+        memo,
+        messages,
+    })
+}
+
+// `pub_key` is a compressed secp256k1 key
+pub fn pub_key_to_address(pub_key: &[u8]) -> Option<String> {
+    let hash = Ripemd160::digest(Sha256::digest(pub_key));
+
+    let hrp = Hrp::parse(ADDRESS_PREFIX).ok()?;
+
+    bech32::encode::<Bech32>(hrp, &hash).ok()
+}
+
+impl From<ProtoCoin> for Coin {
+    fn from(ProtoCoin { denom, amount }: ProtoCoin) -> Self {
+        Self { denom, amount }
+    }
+}
+
+#[derive(prost::Message)]
+struct TxRaw {
+    #[prost(bytes = "vec", tag = "1")]
+    body_bytes: prost::alloc::vec::Vec<u8>,
+
+    #[prost(bytes = "vec", tag = "2")]
+    auth_info_bytes: prost::alloc::vec::Vec<u8>,
+}
+
+#[derive(prost::Message)]
+struct TxBody {
+    #[prost(message, repeated, tag = "1")]
+    messages: prost::alloc::vec::Vec<ProtoAny>,
+
+    #[prost(string, tag = "2")]
+    memo: prost::alloc::string::String,
+}
+
+#[derive(prost::Message)]
+struct AuthInfo {
+    #[prost(message, repeated, tag = "1")]
+    signer_infos: prost::alloc::vec::Vec<SignerInfo>,
+
+    #[prost(message, optional, tag = "2")]
+    fee: Option<ProtoFee>,
+}
+
+#[derive(prost::Message)]
+struct SignerInfo {
+    #[prost(message, optional, tag = "1")]
+    public_key: Option<ProtoAny>,
+}
+
+#[derive(prost::Message)]
+struct PubKey {
+    #[prost(bytes = "vec", tag = "1")]
+    key: prost::alloc::vec::Vec<u8>,
+}
+
+#[derive(prost::Message)]
+struct ProtoFee {
+    #[prost(message, repeated, tag = "1")]
+    amount: prost::alloc::vec::Vec<ProtoCoin>,
+
+    #[prost(uint64, tag = "2")]
+    gas_limit: u64,
+}
+
+#[derive(prost::Message)]
+struct ProtoAny {
+    #[prost(string, tag = "1")]
+    type_url: prost::alloc::string::String,
+
+    #[prost(bytes = "vec", tag = "2")]
+    value: prost::alloc::vec::Vec<u8>,
+}
+
+#[derive(prost::Message)]
+struct ProtoCoin {
+    #[prost(string, tag = "1")]
+    denom: prost::alloc::string::String,
+
+    #[prost(string, tag = "2")]
+    amount: prost::alloc::string::String,
+}
+
+#[derive(prost::Message)]
+struct MsgExecuteContract {
+    #[prost(string, tag = "1")]
+    sender: prost::alloc::string::String,
+
+    #[prost(string, tag = "2")]
+    contract: prost::alloc::string::String,
+
+    #[prost(bytes = "vec", tag = "3")]
+    msg: prost::alloc::vec::Vec<u8>,
+
+    #[prost(message, repeated, tag = "5")]
+    funds: prost::alloc::vec::Vec<ProtoCoin>,
+}
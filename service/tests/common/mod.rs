@@ -0,0 +1,13 @@
+// each test crate uses only part of the shared vectors
+#![allow(dead_code)]
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+
+// a cw721 transfer_nft tx signed with a secp256k1 key, taken from a mainnet stream message
+pub static TX_HASH: &str = "9C6730D42D92169CC8789D395CC7E8F6C8E0B2C9A09EA8985E6CA4B632B19D76";
+
+static TX_BASE64: &str = "CvcBCvQBCiQvY29zbXdhc20ud2FzbS52MS5Nc2dFeGVjdXRlQ29udHJhY3QSywEKKnNlaTF6amdsZmw5NTh1aHJqa3ZlenBqbm5sdmsydnN5dTV1OTNtODY5NRI+c2VpMWhjcThwaHprYXJuNm5yN3drM3k1cXdoN2p5ZnpmY2toOXhkd2owcmNwZWFncmR6N3JyZ3N4czhqenYaXXsidHJhbnNmZXJfbmZ0Ijp7InJlY2lwaWVudCI6InNlaTFobnRmeXdncXFqOTV2OXVyM2RzaHp5ZDlkaGU1d3Fmd204eGNuNiIsInRva2VuX2lkIjoiMTM1OCJ9fRJkCk4KRgofL2Nvc21vcy5jcnlwdG8uc2VjcDI1NmsxLlB1YktleRIjCiECVkbesant4pcifMz48nOPb9wv3d98EdWneIUawGtCN8wSBAoCCAESEgoMCgR1c2VpEgQ0OTEyEMD+DhpAMB9kpRLjvUqDuUmDLgif4FpcDolE406Y4EK/TQpqygIm/Xsielxgjo8lDdVfIXggkeBFOzKTtBE2Hy2zJN3Sdg==";
+
+pub fn tx_bytes() -> Vec<u8> {
+    BASE64_STANDARD.decode(TX_BASE64).unwrap()
+}
@@ -0,0 +1,35 @@
+mod common;
+
+use service::parse_tx_hash;
+use sha2::{Digest, Sha256};
+
+#[test]
+fn tx_hash_is_hex_decoded() {
+    let hash = parse_tx_hash(common::TX_HASH).unwrap();
+
+    assert_eq!(
+        hash.as_bytes(),
+        Sha256::digest(common::tx_bytes()).as_slice()
+    );
+}
+
+#[test]
+fn tx_hash_accepts_lowercase_and_0x_prefix() {
+    let hash = parse_tx_hash(common::TX_HASH).unwrap();
+
+    assert_eq!(
+        parse_tx_hash(&common::TX_HASH.to_lowercase()).unwrap(),
+        hash
+    );
+    assert_eq!(
+        parse_tx_hash(&format!("0x{}", common::TX_HASH)).unwrap(),
+        hash
+    );
+}
+
+#[test]
+fn tx_hash_rejects_malformed_input() {
+    assert!(parse_tx_hash(&format!("0x0x{}", common::TX_HASH)).is_err());
+    assert!(parse_tx_hash(&common::TX_HASH[1..]).is_err());
+    assert!(parse_tx_hash(&common::TX_HASH.replace('9', "Z")).is_err());
+}
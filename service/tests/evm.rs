@@ -0,0 +1,298 @@
+use prost::Message;
+use service::{
+    decode_abi_string, decode_evm_logs, encode_call, topic_to_decimal, EvmLog, ItemType,
+    ORDER_FULFILLED_TOPIC, TOKEN_URI_SELECTOR, TRANSFER_TOPIC, ZERO_ADDRESS,
+};
+
+static CONTRACT: &str = "0x7a1b7a2c8b3d1bd1e3c6e1e0dfb4b9e2c4d5f6a7";
+static SELLER: &str = "0x1111111111111111111111111111111111111111";
+static BUYER: &str = "0x2222222222222222222222222222222222222222";
+static FEE_RECIPIENT: &str = "0x3333333333333333333333333333333333333333";
+
+static EVM_RESPONSE_TYPE_URL: &str = "/seiprotocol.seichain.evm.MsgEVMTransactionResponse";
+
+// mirrors of the messages sei puts in the result data of a tx
+#[derive(prost::Message)]
+struct TypedData {
+    #[prost(string, tag = "1")]
+    type_url: String,
+
+    #[prost(bytes = "vec", tag = "2")]
+    value: Vec<u8>,
+}
+
+#[derive(prost::Message)]
+struct TxMsgData {
+    #[prost(message, repeated, tag = "1")]
+    data: Vec<TypedData>,
+
+    #[prost(message, repeated, tag = "2")]
+    msg_responses: Vec<TypedData>,
+}
+
+#[derive(prost::Message)]
+struct MsgEvmTransactionResponse {
+    #[prost(uint64, tag = "1")]
+    gas_used: u64,
+
+    #[prost(message, repeated, tag = "5")]
+    logs: Vec<Log>,
+}
+
+#[derive(prost::Message)]
+struct Log {
+    #[prost(string, tag = "1")]
+    address: String,
+
+    #[prost(string, repeated, tag = "2")]
+    topics: Vec<String>,
+
+    #[prost(bytes = "vec", tag = "3")]
+    data: Vec<u8>,
+
+    #[prost(uint32, tag = "4")]
+    index: u32,
+
+    #[prost(bool, tag = "5")]
+    synthetic: bool,
+}
+
+fn address_topic(address: &str) -> String {
+    format!("0x{:0>64}", address.trim_start_matches("0x"))
+}
+
+fn uint_word(value: u128) -> Vec<u8> {
+    let mut word = vec![0u8; 16];
+    word.extend(value.to_be_bytes());
+    word
+}
+
+fn address_word(address: &str) -> Vec<u8> {
+    let hex = address.trim_start_matches("0x");
+    let mut word = vec![0u8; 12];
+    word.extend(
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()),
+    );
+    word
+}
+
+fn transfer_log(contract: &str, token_id: u128, synthetic: bool) -> Log {
+    Log {
+        address: contract.to_uppercase().replace("0X", "0x"),
+        topics: vec![
+            TRANSFER_TOPIC.to_owned(),
+            address_topic(SELLER),
+            address_topic(BUYER),
+            format!("0x{:064x}", token_id),
+        ],
+        data: vec![],
+        index: 0,
+        synthetic,
+    }
+}
+
+fn transfer_log_as_evm_log() -> EvmLog {
+    let log = transfer_log(CONTRACT, 1, false);
+
+    EvmLog {
+        address: log.address,
+        topics: log.topics,
+        data: log.data,
+        index: log.index,
+        synthetic: log.synthetic,
+    }
+}
+
+fn evm_response(logs: Vec<Log>) -> TypedData {
+    TypedData {
+        type_url: EVM_RESPONSE_TYPE_URL.to_owned(),
+        value: MsgEvmTransactionResponse {
+            gas_used: 21000,
+            logs,
+        }
+        .encode_to_vec(),
+    }
+}
+
+// a listing of token 7 bought for 100 sei, 95 to the seller and 5 of fees
+fn order_fulfilled_log() -> EvmLog {
+    let mut data = vec![];
+    data.extend(vec![0xab; 32]);
+    data.extend(address_word(BUYER));
+    data.extend(uint_word(4 * 32));
+    data.extend(uint_word((4 + 1 + 4) * 32));
+
+    data.extend(uint_word(1));
+    data.extend(uint_word(2));
+    data.extend(address_word(CONTRACT));
+    data.extend(uint_word(7));
+    data.extend(uint_word(1));
+
+    data.extend(uint_word(2));
+    for (amount, recipient) in [(95, SELLER), (5, FEE_RECIPIENT)] {
+        data.extend(uint_word(0));
+        data.extend(address_word(ZERO_ADDRESS));
+        data.extend(uint_word(0));
+        data.extend(uint_word(amount * 1_000_000_000_000_000_000));
+        data.extend(address_word(recipient));
+    }
+
+    EvmLog {
+        address: "0x0000000000000068f116a894984e2db1123eb395".to_owned(),
+        topics: vec![
+            ORDER_FULFILLED_TOPIC.to_owned(),
+            address_topic(SELLER),
+            address_topic(FEE_RECIPIENT),
+        ],
+        data,
+        index: 1,
+        synthetic: false,
+    }
+}
+
+#[test]
+fn converts_topics_to_decimal() {
+    assert_eq!(topic_to_decimal(&format!("0x{:064x}", 0)).unwrap(), "0");
+    assert_eq!(topic_to_decimal(&format!("0x{:064x}", 42)).unwrap(), "42");
+    assert_eq!(
+        topic_to_decimal(&format!("0x{:064x}", u128::MAX)).unwrap(),
+        u128::MAX.to_string()
+    );
+    assert_eq!(
+        topic_to_decimal(&format!("0x{}", "f".repeat(64))).unwrap(),
+        "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+    );
+}
+
+#[test]
+fn rejects_non_hex_topics() {
+    assert_eq!(topic_to_decimal("0xzz"), None);
+}
+
+#[test]
+fn decodes_logs_of_evm_responses() {
+    let data = TxMsgData {
+        data: vec![],
+        msg_responses: vec![
+            TypedData {
+                type_url: "/cosmos.bank.v1beta1.MsgSendResponse".to_owned(),
+                value: vec![],
+            },
+            evm_response(vec![transfer_log(CONTRACT, 1358, false)]),
+        ],
+    }
+    .encode_to_vec();
+
+    let logs = decode_evm_logs(&data).unwrap();
+
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].address, CONTRACT);
+    assert!(!logs[0].synthetic);
+
+    let transfer = logs[0].to_erc721_transfer().unwrap();
+
+    assert_eq!(transfer.contract, CONTRACT);
+    assert_eq!(transfer.from, SELLER);
+    assert_eq!(transfer.to, BUYER);
+    assert_eq!(transfer.token_id, "1358");
+}
+
+#[test]
+fn decodes_logs_of_legacy_msg_data() {
+    let data = TxMsgData {
+        data: vec![TypedData {
+            type_url: "/seiprotocol.seichain.evm.MsgEVMTransaction".to_owned(),
+            value: MsgEvmTransactionResponse {
+                gas_used: 21000,
+                logs: vec![transfer_log(CONTRACT, 1, true)],
+            }
+            .encode_to_vec(),
+        }],
+        msg_responses: vec![],
+    }
+    .encode_to_vec();
+
+    let logs = decode_evm_logs(&data).unwrap();
+
+    assert_eq!(logs.len(), 1);
+    assert!(logs[0].synthetic);
+}
+
+#[test]
+fn decodes_nothing_without_evm_messages() {
+    let data = TxMsgData {
+        data: vec![],
+        msg_responses: vec![TypedData {
+            type_url: "/cosmwasm.wasm.v1.MsgExecuteContractResponse".to_owned(),
+            value: vec![1, 2, 3],
+        }],
+    }
+    .encode_to_vec();
+
+    assert!(decode_evm_logs(&data).unwrap().is_empty());
+    assert!(decode_evm_logs(&[0xff, 0xff]).is_err());
+}
+
+#[test]
+fn decodes_seaport_order_fulfilled() {
+    let order = order_fulfilled_log().to_order_fulfilled().unwrap();
+
+    assert_eq!(order.offerer, SELLER);
+    assert_eq!(order.recipient, BUYER);
+    assert!(order.trades(CONTRACT, "7"));
+    assert!(!order.trades(CONTRACT, "8"));
+
+    assert_eq!(order.offer.len(), 1);
+    assert_eq!(order.offer[0].item_type, ItemType::Erc721);
+    assert_eq!(order.offer[0].recipient, None);
+
+    assert_eq!(order.consideration.len(), 2);
+    assert_eq!(order.consideration[0].item_type, ItemType::Native);
+    assert_eq!(order.consideration[0].amount, "95000000000000000000");
+    assert_eq!(order.consideration[0].recipient.as_deref(), Some(SELLER));
+    assert_eq!(order.consideration[1].amount, "5000000000000000000");
+}
+
+#[test]
+fn ignores_truncated_order_fulfilled() {
+    let mut log = order_fulfilled_log();
+    log.data.truncate(log.data.len() - 32);
+
+    assert!(log.to_order_fulfilled().is_none());
+    assert!(transfer_log_as_evm_log().to_order_fulfilled().is_none());
+}
+
+#[test]
+fn encodes_and_decodes_abi_calls() {
+    let call = encode_call(TOKEN_URI_SELECTOR, &["258"]).unwrap();
+
+    assert_eq!(call[..4], TOKEN_URI_SELECTOR);
+    assert_eq!(call[4..], uint_word(258));
+    assert!(encode_call(TOKEN_URI_SELECTOR, &["12a"]).is_none());
+
+    let uri = "ipfs://bafy/1.json";
+    let mut data = uint_word(32);
+    data.extend(uint_word(uri.len() as u128));
+    data.extend(uri.as_bytes());
+    data.resize(data.len() + 32 - uri.len() % 32, 0);
+
+    assert_eq!(decode_abi_string(&data).as_deref(), Some(uri));
+    assert_eq!(decode_abi_string(&data[..64]), None);
+}
+
+#[test]
+fn rejects_abi_strings_with_oversized_length() {
+    let mut data = uint_word(32);
+    data.extend(vec![0xff; 32]);
+    data.extend(b"ipfs");
+
+    assert_eq!(decode_abi_string(&data), None);
+
+    let mut data = uint_word(32);
+    data.extend(uint_word(u64::MAX as u128));
+    data.extend(b"ipfs");
+
+    assert_eq!(decode_abi_string(&data), None);
+}
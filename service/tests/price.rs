@@ -0,0 +1,93 @@
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+use service::{to_display_amount, unknown_denom, DenomPrice, PriceOracle, StaticPriceOracle};
+
+static USDC: &str = "ibc/CA6FBFAF399474A06263E10D0CE5AEBBE15189D6D4B2DD9ADE61007E68EB9DB0";
+
+fn decimal(value: &str) -> Decimal {
+    Decimal::from_str(value).unwrap()
+}
+
+fn oracle() -> StaticPriceOracle {
+    StaticPriceOracle::new([
+        DenomPrice {
+            denom: "usei".to_owned(),
+            symbol: "SEI".to_owned(),
+            decimals: 6,
+            usd: Some(decimal("0.5")),
+        },
+        DenomPrice {
+            denom: USDC.to_owned(),
+            symbol: "USDC".to_owned(),
+            decimals: 6,
+            usd: Some(decimal("1")),
+        },
+    ])
+}
+
+#[test]
+fn quotes_sei_in_display_units() {
+    let quote = oracle().quote(decimal("2500000"), "usei");
+
+    assert_eq!(quote.amount, decimal("2.5"));
+    assert_eq!(quote.symbol, "SEI");
+    assert_eq!(quote.sei, Some(decimal("2.5")));
+    assert_eq!(quote.usd, Some(decimal("1.25")));
+}
+
+#[test]
+fn quotes_other_denoms_in_sei_through_usd() {
+    let quote = oracle().quote(decimal("3000000"), USDC);
+
+    assert_eq!(quote.amount, decimal("3"));
+    assert_eq!(quote.symbol, "USDC");
+    assert_eq!(quote.usd, Some(decimal("3")));
+    assert_eq!(quote.sei, Some(decimal("6")));
+}
+
+#[test]
+fn quotes_unpriced_denoms_without_value() {
+    let quote = oracle().quote(decimal("1000000"), "factory/sei1creator/token");
+
+    assert_eq!(quote.amount, decimal("1"));
+    assert_eq!(quote.symbol, "TOKEN");
+    assert_eq!(quote.sei, None);
+    assert_eq!(quote.usd, None);
+}
+
+#[test]
+fn quotes_sei_without_usd_price() {
+    let quote = StaticPriceOracle::default().quote(decimal("1000000"), USDC);
+
+    assert_eq!(quote.usd, None);
+    assert_eq!(quote.sei, None);
+}
+
+#[test]
+fn names_unknown_denoms() {
+    assert_eq!(unknown_denom(USDC).symbol, "IBC/CA6FBF");
+    assert_eq!(unknown_denom("factory/sei1creator/usdt").symbol, "USDT");
+    assert_eq!(unknown_denom("uatom").symbol, "ATOM");
+    assert_eq!(unknown_denom("wei").symbol, "WEI");
+    assert_eq!(unknown_denom("uatom").decimals, 6);
+}
+
+#[test]
+fn scales_amounts_to_display_units() {
+    assert_eq!(to_display_amount(decimal("1500000"), 6), decimal("1.5"));
+    assert_eq!(to_display_amount(decimal("42"), 0), decimal("42"));
+    assert_eq!(
+        to_display_amount(decimal("1230000000000000000"), 18),
+        decimal("1.23")
+    );
+}
+
+#[test]
+fn scales_amounts_beyond_u64_decimals() {
+    assert_eq!(
+        to_display_amount(decimal("5000000000000000000000000"), 24),
+        decimal("5")
+    );
+    assert_eq!(to_display_amount(decimal("1"), 40), Decimal::ZERO);
+}
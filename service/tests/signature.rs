@@ -0,0 +1,119 @@
+use base64::{prelude::BASE64_STANDARD, Engine};
+use service::{pub_key_to_address, verify_arbitrary_signature, SignatureError};
+
+// produced off-chain by signing the ADR-036 amino doc of MESSAGE with an independent secp256k1 library
+static PUB_KEY: &str = "A5HfQcL3Tt1p8LDHLDHMHr5nqpmaDqJ0eUy3jzNL8jV0";
+static ADDRESS: &str = "sei1kt90gn6dschhhrgute4ufpx5akcenutd3305hn";
+static SIGNATURE: &str =
+    "H1l/3q2mP0B0WZQanga0pY5TS1cIS8JW4LrLNTmwDzdq5Ub62m6twTTd3PJuPww7M26svYUvojwGsh4fs2RsWg==";
+static MESSAGE: &str = "Sign in to mrktoxide\n\nAddress: sei1kt90gn6dschhhrgute4ufpx5akcenutd3305hn\nNonce: 5f2b3c1d9e8a4b7c6d5e4f3a2b1c0d9e";
+
+static OTHER_PUB_KEY: &str = "A0cuwon9h0Q+3Ngz4gzz/UZbCU9EtNzUy93rIUTkeFUt";
+static OTHER_ADDRESS: &str = "sei137agce30nm5jxxpvqccgc5c87ahqeuuppsywqg";
+
+fn decode(value: &str) -> Vec<u8> {
+    BASE64_STANDARD.decode(value).unwrap()
+}
+
+fn verify(
+    signer: &str,
+    message: &str,
+    pub_key: &str,
+    signature: &[u8],
+) -> Result<(), SignatureError> {
+    verify_arbitrary_signature(signer, message.as_bytes(), &decode(pub_key), signature)
+}
+
+#[test]
+fn derives_address_from_compressed_pub_key() {
+    assert_eq!(
+        pub_key_to_address(&decode(PUB_KEY)).as_deref(),
+        Some(ADDRESS)
+    );
+    assert_eq!(
+        pub_key_to_address(&decode(OTHER_PUB_KEY)).as_deref(),
+        Some(OTHER_ADDRESS)
+    );
+}
+
+#[test]
+fn accepts_signature_of_the_signer() {
+    assert_eq!(
+        verify(ADDRESS, MESSAGE, PUB_KEY, &decode(SIGNATURE)),
+        Ok(())
+    );
+}
+
+#[test]
+fn accepts_high_s_form_of_the_signature() {
+    // secp256k1 order, s and n - s both verify once normalized
+    let n = hex_bytes("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141");
+
+    let mut signature = decode(SIGNATURE);
+    let s = signature[32..].to_vec();
+
+    let mut borrow = 0i16;
+
+    for index in (0..32).rev() {
+        let value = n[index] as i16 - s[index] as i16 - borrow;
+        borrow = (value < 0) as i16;
+        signature[32 + index] = value.rem_euclid(256) as u8;
+    }
+
+    assert_eq!(verify(ADDRESS, MESSAGE, PUB_KEY, &signature), Ok(()));
+}
+
+#[test]
+fn rejects_pub_key_of_another_signer() {
+    assert_eq!(
+        verify(ADDRESS, MESSAGE, OTHER_PUB_KEY, &decode(SIGNATURE)),
+        Err(SignatureError::Signer)
+    );
+    assert_eq!(
+        verify(OTHER_ADDRESS, MESSAGE, PUB_KEY, &decode(SIGNATURE)),
+        Err(SignatureError::Signer)
+    );
+}
+
+#[test]
+fn rejects_tampered_signature() {
+    let mut signature = decode(SIGNATURE);
+    signature[10] ^= 1;
+
+    assert_eq!(
+        verify(ADDRESS, MESSAGE, PUB_KEY, &signature),
+        Err(SignatureError::Signature)
+    );
+    assert_eq!(
+        verify(ADDRESS, MESSAGE, PUB_KEY, &signature[..63]),
+        Err(SignatureError::Signature)
+    );
+}
+
+#[test]
+fn rejects_signature_replayed_for_another_nonce() {
+    let message = MESSAGE.replace(
+        "5f2b3c1d9e8a4b7c6d5e4f3a2b1c0d9e",
+        "0d9e1c2b3a4f5e6d7c8b9a0f1e2d3c4b",
+    );
+
+    assert_eq!(
+        verify(ADDRESS, &message, PUB_KEY, &decode(SIGNATURE)),
+        Err(SignatureError::Signature)
+    );
+}
+
+#[test]
+fn rejects_malformed_pub_key() {
+    assert_eq!(
+        verify_arbitrary_signature(ADDRESS, MESSAGE.as_bytes(), &[9; 33], &decode(SIGNATURE)),
+        Err(SignatureError::PubKey)
+    );
+}
+
+fn hex_bytes(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).unwrap())
+        .collect()
+}
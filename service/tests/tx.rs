@@ -0,0 +1,90 @@
+mod common;
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use service::{decode_tx, pub_key_to_address, ExecuteContractMsg};
+
+static SIGNER: &str = "sei1zjglfl958uhrjkvezpjnnlvk2vsyu5u93m8695";
+static CONTRACT: &str = "sei1hcq8phzkarn6nr7wk3y5qwh7jyfzfckh9xdwj0rcpeagrdz7rrgsxs8jzv";
+
+// compressed secp256k1 key in the auth info of the tx
+static SIGNER_PUB_KEY: &str = "AlZG3rGp7eKXInzM+PJzj2/cL93ffBHVp3iFGsBrQjfM";
+
+#[test]
+fn decodes_execute_contract_msg() {
+    let tx = decode_tx(&common::tx_bytes()).unwrap();
+
+    assert_eq!(tx.messages.len(), 1);
+
+    let message = &tx.messages[0];
+
+    assert_eq!(message.sender, SIGNER);
+    assert_eq!(message.contract, CONTRACT);
+    assert_eq!(
+        message.msg,
+        serde_json::json!({
+            "transfer_nft": {
+                "recipient": "sei1hntfywgqqj95v9ur3dshzyd9dhe5wqfwm8xcn6",
+                "token_id": "1358",
+            }
+        })
+    );
+    assert!(message.funds.is_empty());
+}
+
+#[test]
+fn decodes_signer_and_fee() {
+    let tx = decode_tx(&common::tx_bytes()).unwrap();
+
+    assert_eq!(tx.signer.as_deref(), Some(SIGNER));
+    assert_eq!(tx.memo, "");
+    assert_eq!(tx.fee.gas_limit, 245568);
+    assert_eq!(tx.fee.amount.len(), 1);
+    assert_eq!(tx.fee.amount[0].denom, "usei");
+    assert_eq!(tx.fee.amount[0].amount, "4912");
+}
+
+#[test]
+fn rejects_bytes_that_are_not_a_tx() {
+    assert!(decode_tx(b"not a tx").is_err());
+}
+
+#[test]
+fn derives_signer_address_from_pub_key() {
+    let pub_key = BASE64_STANDARD.decode(SIGNER_PUB_KEY).unwrap();
+
+    assert_eq!(pub_key_to_address(&pub_key).as_deref(), Some(SIGNER));
+}
+
+fn buy_now(msg: serde_json::Value) -> ExecuteContractMsg {
+    ExecuteContractMsg {
+        sender: SIGNER.to_owned(),
+        contract: service::PALLET_CONTRACT_ADDRESS.to_owned(),
+        msg,
+        funds: vec![],
+    }
+}
+
+#[test]
+fn names_nft_matches_address_and_token_id_structurally() {
+    let message = buy_now(serde_json::json!({
+        "buy_now": { "nft": { "address": CONTRACT, "token_id": "1358" } }
+    }));
+
+    assert!(message.names_nft("buy_now", CONTRACT, "1358"));
+    assert!(!message.names_nft("buy_now", CONTRACT, "135"));
+    assert!(!message.names_nft("buy_now", SIGNER, "1358"));
+    assert!(!message.names_nft("cancel_auction", CONTRACT, "1358"));
+}
+
+#[test]
+fn names_nft_ignores_values_found_elsewhere_in_msg() {
+    let message = buy_now(serde_json::json!({
+        "buy_now": {
+            "nft": { "address": CONTRACT, "token_id": "1" },
+            "memo": format!("{} 1358", CONTRACT),
+            "token_id": "1358",
+        }
+    }));
+
+    assert!(!message.names_nft("buy_now", CONTRACT, "1358"));
+}